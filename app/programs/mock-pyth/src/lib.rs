@@ -0,0 +1,33 @@
+//! Mock Pyth Oracle Program
+//!
+//! Loaded at the Pyth program address on the local test validator, so tests
+//! can publish prices into the fixture price accounts with a current
+//! timestamp. Never deployed to a live cluster.
+
+use anchor_lang::prelude::*;
+
+declare_id!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+
+#[program]
+pub mod mock_pyth {
+    use super::*;
+
+    /// Overwrite part of a price account's data, starting at `offset`
+    pub fn write_price(ctx: Context<WritePrice>, offset: u32, data: Vec<u8>) -> Result<()> {
+        let mut price = ctx.accounts.price.try_borrow_mut_data()?;
+        let start = offset as usize;
+        let end = start
+            .checked_add(data.len())
+            .filter(|&end| end <= price.len())
+            .ok_or(ProgramError::AccountDataTooSmall)?;
+        price[start..end].copy_from_slice(&data);
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct WritePrice<'info> {
+    /// CHECK: raw Pyth price account, written as is
+    #[account(mut, owner = crate::ID)]
+    pub price: UncheckedAccount<'info>,
+}
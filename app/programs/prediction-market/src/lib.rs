@@ -4,32 +4,114 @@
  */
 
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, CloseAccount, Mint, MintTo, Token, TokenAccount, Transfer};
 
 declare_id!("BMmGykphijTgvB7WMim9UVqi9976iibKf6uYAiGXC7Mc");
 
+/// Maximum number of outcomes on a categorical event
+pub const MAX_OUTCOMES: usize = 16;
+/// Maximum length of a categorical outcome name
+pub const MAX_OUTCOME_NAME_LEN: usize = 32;
+/// Maximum number of oracle committee members
+pub const MAX_COMMITTEE_MEMBERS: usize = 10;
+/// Maximum number of resolvers events can be bound to
+pub const MAX_ALLOWED_RESOLVERS: usize = 32;
+/// Maximum age of a price used for resolution
+pub const MAX_PRICE_AGE: i64 = 60;
+/// Maximum price confidence interval, as a fraction of the price
+pub const MAX_PRICE_CONFIDENCE_BPS: u64 = 100;
+/// Default time an outcome proposal stays open to disputes
+pub const DEFAULT_DISPUTE_WINDOW: i64 = 24 * 60 * 60;
+/// Default DOOM bond to propose or dispute an outcome (9 decimals)
+pub const DEFAULT_DISPUTE_BOND: u64 = 100_000_000_000;
+/// Default DOOM bond to create an event (9 decimals)
+pub const DEFAULT_CREATION_BOND: u64 = 10_000_000_000;
+/// Default share of the platform fee paid to event creators, in basis points of the fee
+pub const DEFAULT_CREATOR_FEE_BPS: u16 = 1000;
+/// Default smallest bet (9 decimals)
+pub const DEFAULT_MIN_BET: u64 = 1_000_000;
+/// Default share of a withdrawn stake kept in the pool, in basis points
+pub const DEFAULT_EXIT_PENALTY_BPS: u16 = 500;
+
+/// Fixed-point scale of the LMSR math
+pub const LMSR_SCALE: u128 = 1_000_000_000_000;
+/// ln 2 in `LMSR_SCALE` fixed point, rounded up
+pub const LMSR_LN_2: u128 = 693_147_180_560;
+
+/// Default time after the resolution deadline after which a finished event
+/// can be closed with bets still open, sweeping what's left to the treasury
+pub const DEFAULT_SWEEP_WINDOW: i64 = 30 * 24 * 60 * 60;
+/// Pause flags, combined in `PlatformConfig.paused`
+pub const PAUSE_CREATION: u8 = 1 << 0;
+pub const PAUSE_BETTING: u8 = 1 << 1;
+pub const PAUSE_RESOLUTION: u8 = 1 << 2;
+pub const PAUSE_CLAIMS: u8 = 1 << 3;
+pub const PAUSE_WITHDRAWALS: u8 = 1 << 4;
+pub const PAUSE_ALL: u8 =
+    PAUSE_CREATION | PAUSE_BETTING | PAUSE_RESOLUTION | PAUSE_CLAIMS | PAUSE_WITHDRAWALS;
+
+/// Pyth oracle program on devnet, owner of price accounts events can resolve from
+pub mod pyth {
+    use super::*;
+    declare_id!("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+}
+
 #[program]
 pub mod prediction_market {
     use super::*;
 
     /// Initialize the platform configuration
+    ///
+    /// `config_delay` is how long fee and oracle changes wait before they
+    /// can be applied.
     pub fn initialize_platform(
         ctx: Context<InitializePlatform>,
         fee_basis_points: u16,
+        config_delay: i64,
     ) -> Result<()> {
         require!(
             fee_basis_points <= 10000,
             PredictionError::InvalidFeeBasisPoints
         );
+        require!(config_delay >= 0, PredictionError::InvalidConfigDelay);
 
         let config = &mut ctx.accounts.platform_config;
         config.authority = ctx.accounts.authority.key();
+        config.pending_authority = None;
+        config.pauser = ctx.accounts.authority.key();
+        config.fee_manager = ctx.accounts.authority.key();
+        config.moderator = ctx.accounts.authority.key();
         config.oracle = ctx.accounts.authority.key();
+        config.doom_mint = ctx.accounts.doom_mint.key();
+        config.life_mint = ctx.accounts.life_mint.key();
         config.fee_basis_points = fee_basis_points;
-        config.paused = false;
+        config.config_delay = config_delay;
+        config.pending_fee_basis_points = None;
+        config.pending_oracle = None;
+        config.pending_config_delay = None;
+        config.pending_creator_fee_bps = None;
+        config.pending_config_effective_at = 0;
+        config.paused = 0;
         config.total_doom_fees = 0;
         config.total_life_fees = 0;
         config.total_events = 0;
         config.total_bets = 0;
+        config.dispute_window = DEFAULT_DISPUTE_WINDOW;
+        config.dispute_bond = DEFAULT_DISPUTE_BOND;
+        config.creation_bond = DEFAULT_CREATION_BOND;
+        config.creator_fee_bps = DEFAULT_CREATOR_FEE_BPS;
+        config.betting_cutoff = 0;
+        config.exit_penalty_bps = DEFAULT_EXIT_PENALTY_BPS;
+        config.sweep_window = DEFAULT_SWEEP_WINDOW;
+        config.bet_limits = BetLimits {
+            min_bet: DEFAULT_MIN_BET,
+            max_bet: 0,
+            max_user_stake: 0,
+            max_side_share_bps: 0,
+        };
+        config.bond_vault_bump = ctx.bumps.bond_vault;
+        config.doom_treasury_bump = ctx.bumps.doom_treasury;
+        config.life_treasury_bump = ctx.bumps.life_treasury;
         config.bump = ctx.bumps.platform_config;
 
         msg!("Platform initialized with {}bps fee", fee_basis_points);
@@ -37,6 +119,10 @@ pub mod prediction_market {
     }
 
     /// Create a new prediction event
+    ///
+    /// `resolver` optionally binds an allowlisted resolver that resolves the
+    /// event instead of the platform oracle. The creator escrows the
+    /// platform's creation bond, returned by `return_creation_bond`.
     pub fn create_event(
         ctx: Context<CreateEvent>,
         event_id: u64,
@@ -44,6 +130,7 @@ pub mod prediction_market {
         description: String,
         deadline: i64,
         resolution_deadline: i64,
+        resolver: Option<Pubkey>,
     ) -> Result<()> {
         require!(
             !title.is_empty() && title.len() <= 128,
@@ -63,6 +150,48 @@ pub mod prediction_market {
             resolution_deadline > deadline,
             PredictionError::InvalidResolutionDeadline
         );
+        let betting_closes_at = deadline
+            .checked_sub(ctx.accounts.platform_config.betting_cutoff)
+            .ok_or(PredictionError::Overflow)?;
+        require!(
+            betting_closes_at > clock.unix_timestamp,
+            PredictionError::InvalidBettingCutoff
+        );
+        if let Some(resolver) = resolver {
+            let allowlist = ctx
+                .accounts
+                .resolver_allowlist
+                .as_ref()
+                .ok_or(PredictionError::ResolverNotAllowed)?;
+            require!(
+                allowlist.resolvers.contains(&resolver),
+                PredictionError::ResolverNotAllowed
+            );
+        }
+
+        let creation_bond = ctx.accounts.platform_config.creation_bond;
+        if creation_bond > 0 {
+            let creator_doom_account = ctx
+                .accounts
+                .creator_doom_account
+                .as_ref()
+                .ok_or(PredictionError::CreationBondRequired)?;
+            let bond_vault = ctx
+                .accounts
+                .bond_vault
+                .as_ref()
+                .ok_or(PredictionError::CreationBondRequired)?;
+
+            let transfer = Transfer {
+                from: creator_doom_account.to_account_info(),
+                to: bond_vault.to_account_info(),
+                authority: ctx.accounts.creator.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer),
+                creation_bond,
+            )?;
+        }
 
         let event = &mut ctx.accounts.event;
         event.event_id = event_id;
@@ -70,28 +199,307 @@ pub mod prediction_market {
         event.title = title;
         event.description = description;
         event.deadline = deadline;
+        event.betting_closes_at = betting_closes_at;
         event.resolution_deadline = resolution_deadline;
+        event.resolver = resolver;
+        event.fee_basis_points = ctx.accounts.platform_config.fee_basis_points;
+        event.creator_fee_bps = ctx.accounts.platform_config.creator_fee_bps;
+        event.bet_limits = ctx.accounts.platform_config.bet_limits;
+        event.exit_penalty_bps = ctx.accounts.platform_config.exit_penalty_bps;
+        event.sweep_window = ctx.accounts.platform_config.sweep_window;
+        event.paused = false;
         event.status = EventStatus::Active;
+        event.kind = EventKind::Binary;
+        event.outcome_count = 2;
+        event.lower_bound = 0;
+        event.upper_bound = 0;
+        event.price_feed = None;
+        event.price_threshold = 0;
+        event.price_expo = 0;
+        event.price_comparator = PriceComparator::Below;
         event.outcome = None;
         event.doom_pool = 0;
         event.life_pool = 0;
+        event.doom_penalty_pool = 0;
+        event.life_penalty_pool = 0;
         event.total_bettors = 0;
+        event.outstanding_bets = 0;
         event.created_at = clock.unix_timestamp;
         event.resolved_at = None;
+        event.proposer = Pubkey::default();
+        event.disputer = None;
+        event.dispute_ends_at = 0;
+        event.bond = 0;
+        event.committee_outcome = None;
+        event.committee_votes = 0;
+        event.oracle_votes = 0;
+        event.settled_stake = 0;
+        event.creation_bond = creation_bond;
+        event.creator_doom_fees = 0;
+        event.creator_life_fees = 0;
+        event.tokenized = false;
+        event.pricing = PricingMode::Parimutuel;
+        event.lmsr_liquidity = 0;
+        event.lmsr_collected = 0;
+        event.doom_vault_bump = ctx.bumps.doom_vault;
+        event.life_vault_bump = ctx.bumps.life_vault;
+        event.doom_share_mint_bump = 0;
+        event.life_share_mint_bump = 0;
         event.bump = ctx.bumps.event;
 
         let platform_config = &mut ctx.accounts.platform_config;
         platform_config.total_events = platform_config.total_events.saturating_add(1);
 
+        let user_stats = &mut ctx.accounts.user_stats;
+        user_stats.init_if_new(ctx.accounts.creator.key(), ctx.bumps.user_stats);
+        user_stats.events_created = user_stats.events_created.saturating_add(1);
+
         msg!("Event {} created: {}", event_id, event.title);
         Ok(())
     }
 
-    /// Place a bet on an event
+    /// Turn a freshly created event into a categorical event with named outcomes
+    ///
+    /// Must be called by the creator before any bets are placed. Each outcome
+    /// then needs its vault created with `initialize_outcome_vault`.
+    pub fn add_event_outcomes(
+        ctx: Context<AddEventOutcomes>,
+        names: Vec<String>,
+    ) -> Result<()> {
+        require!(
+            names.len() >= 2 && names.len() <= MAX_OUTCOMES,
+            PredictionError::InvalidOutcomeCount
+        );
+        require!(
+            names
+                .iter()
+                .all(|name| !name.is_empty() && name.len() <= MAX_OUTCOME_NAME_LEN),
+            PredictionError::InvalidOutcomeName
+        );
+
+        let event = &mut ctx.accounts.event;
+        require!(
+            event.status == EventStatus::Active,
+            PredictionError::EventEnded
+        );
+        require!(
+            event.kind == EventKind::Binary && event.total_bettors == 0,
+            PredictionError::EventHasBets
+        );
+        require!(!event.tokenized, PredictionError::EventTokenized);
+        require!(
+            event.pricing == PricingMode::Parimutuel,
+            PredictionError::LmsrUnsupported
+        );
+        require!(event.price_feed.is_none(), PredictionError::PriceTriggered);
+
+        event.kind = EventKind::Categorical;
+        event.outcome_count = names.len() as u8;
+
+        let event_outcomes = &mut ctx.accounts.event_outcomes;
+        event_outcomes.event = event.key();
+        event_outcomes.pools = vec![0; names.len()];
+        event_outcomes.vault_bumps = vec![0; names.len()];
+        event_outcomes.names = names;
+        event_outcomes.bump = ctx.bumps.event_outcomes;
+
+        msg!(
+            "Event {} is categorical with {} outcomes",
+            event.event_id,
+            event.outcome_count
+        );
+        Ok(())
+    }
+
+    /// Create the DOOM vault for one outcome of a categorical event
+    pub fn initialize_outcome_vault(
+        ctx: Context<InitializeOutcomeVault>,
+        index: u8,
+    ) -> Result<()> {
+        let event_outcomes = &mut ctx.accounts.event_outcomes;
+        require!(
+            (index as usize) < event_outcomes.pools.len(),
+            PredictionError::InvalidOutcome
+        );
+        event_outcomes.vault_bumps[index as usize] = ctx.bumps.outcome_vault;
+
+        msg!(
+            "Outcome vault {} initialized for event {}",
+            index,
+            ctx.accounts.event.event_id
+        );
+        Ok(())
+    }
+
+    /// Turn a freshly created event into a scalar event over a numeric range
+    ///
+    /// Doom bets go long and Life bets go short on the resolved value; payouts
+    /// are interpolated linearly between the bounds. Must be called by the
+    /// creator before any bets are placed.
+    pub fn set_scalar_range(
+        ctx: Context<SetScalarRange>,
+        lower_bound: i64,
+        upper_bound: i64,
+    ) -> Result<()> {
+        require!(
+            lower_bound < upper_bound,
+            PredictionError::InvalidScalarRange
+        );
+
+        let event = &mut ctx.accounts.event;
+        require!(
+            event.status == EventStatus::Active,
+            PredictionError::EventEnded
+        );
+        require!(
+            event.kind != EventKind::Categorical && event.total_bettors == 0,
+            PredictionError::EventHasBets
+        );
+        require!(!event.tokenized, PredictionError::EventTokenized);
+        require!(
+            event.pricing == PricingMode::Parimutuel,
+            PredictionError::LmsrUnsupported
+        );
+        require!(event.price_feed.is_none(), PredictionError::PriceTriggered);
+
+        event.kind = EventKind::Scalar;
+        event.lower_bound = lower_bound;
+        event.upper_bound = upper_bound;
+
+        msg!(
+            "Event {} is scalar over [{}, {}]",
+            event.event_id,
+            lower_bound,
+            upper_bound
+        );
+        Ok(())
+    }
+
+    /// Issue a binary event's positions as DOOM and LIFE share tokens
+    ///
+    /// Creates one SPL mint per side, owned by the event PDA. Bets then mint
+    /// shares 1:1 with their stake, which can be transferred or traded and are
+    /// redeemed by burning them with `redeem_shares`. Must be called by the
+    /// creator before any bets are placed.
+    pub fn create_share_mints(ctx: Context<CreateShareMints>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        require!(
+            event.status == EventStatus::Active,
+            PredictionError::EventEnded
+        );
+        require!(
+            event.kind == EventKind::Binary && event.total_bettors == 0,
+            PredictionError::EventHasBets
+        );
+
+        require!(
+            event.pricing == PricingMode::Parimutuel,
+            PredictionError::LmsrUnsupported
+        );
+
+        event.tokenized = true;
+        event.doom_share_mint_bump = ctx.bumps.doom_share_mint;
+        event.life_share_mint_bump = ctx.bumps.life_share_mint;
+
+        msg!("Event {} positions are tokenized", event.event_id);
+        Ok(())
+    }
+
+    /// Price a binary event with an LMSR market maker of liquidity `b`
+    ///
+    /// Bets then buy `amount` shares at the quoted price in DOOM, and each
+    /// winning share pays 1 DOOM (half on a draw). The platform subsidizes the
+    /// market maker's worst-case loss of `b * ln 2` from the DOOM treasury;
+    /// whatever is left is swept back when the event is closed. Fee manager
+    /// only, before any bets are placed.
+    pub fn enable_lmsr(ctx: Context<EnableLmsr>, liquidity: u64) -> Result<()> {
+        require!(liquidity > 0, PredictionError::InvalidLiquidity);
+
+        let event = &ctx.accounts.event;
+        require!(
+            event.status == EventStatus::Active,
+            PredictionError::EventEnded
+        );
+        require!(
+            event.kind == EventKind::Binary && event.total_bettors == 0,
+            PredictionError::EventHasBets
+        );
+        require!(!event.tokenized, PredictionError::EventTokenized);
+        require!(
+            event.pricing == PricingMode::Parimutuel,
+            PredictionError::LmsrAlreadyEnabled
+        );
+
+        let subsidy = lmsr_cost(0, 0, liquidity)?;
+        require!(
+            ctx.accounts.doom_treasury.amount >= subsidy,
+            PredictionError::InsufficientTreasuryBalance
+        );
+        transfer_from_platform_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.doom_treasury,
+            ctx.accounts.doom_vault.to_account_info(),
+            &ctx.accounts.platform_config,
+            subsidy,
+        )?;
+
+        let event = &mut ctx.accounts.event;
+        event.pricing = PricingMode::Lmsr;
+        event.lmsr_liquidity = liquidity;
+
+        msg!(
+            "Event {} priced by LMSR with b = {}, subsidy {}",
+            event.event_id,
+            liquidity,
+            subsidy
+        );
+        Ok(())
+    }
+
+    /// Make a binary event resolvable from an on-chain price account
+    ///
+    /// The event resolves Doom if the price compares to `threshold` as given by
+    /// `comparator`, Life otherwise. `threshold` uses the feed's exponent. Must
+    /// be called by the creator before any bets are placed.
+    pub fn set_price_trigger(
+        ctx: Context<SetPriceTrigger>,
+        threshold: i64,
+        comparator: PriceComparator,
+    ) -> Result<()> {
+        let price = load_price_feed(&ctx.accounts.price_feed)?;
+
+        let event = &mut ctx.accounts.event;
+        require!(
+            event.status == EventStatus::Active,
+            PredictionError::EventEnded
+        );
+        require!(
+            event.kind == EventKind::Binary && event.total_bettors == 0,
+            PredictionError::EventHasBets
+        );
+
+        event.price_feed = Some(ctx.accounts.price_feed.key());
+        event.price_threshold = threshold;
+        event.price_expo = price.expo;
+        event.price_comparator = comparator;
+
+        msg!(
+            "Event {} resolves from price feed {}: {:?} {}e{}",
+            event.event_id,
+            ctx.accounts.price_feed.key(),
+            comparator,
+            threshold,
+            price.expo
+        );
+        Ok(())
+    }
+
+    /// Place a bet, or on LMSR events buy `amount` shares for at most `max_cost` DOOM
     pub fn place_bet(
         ctx: Context<PlaceBet>,
         outcome: Outcome,
         amount: u64,
+        max_cost: Option<u64>,
     ) -> Result<()> {
         require!(amount > 0, PredictionError::InvalidBetAmount);
 
@@ -101,7 +509,7 @@ pub mod prediction_market {
         let event_key = ctx.accounts.event.key();
         let user_key = ctx.accounts.user.key();
         let event_status = ctx.accounts.event.status;
-        let event_deadline = ctx.accounts.event.deadline;
+        let betting_closes_at = ctx.accounts.event.betting_closes_at;
         let event_id = ctx.accounts.event.event_id;
 
         require!(
@@ -109,187 +517,3511 @@ pub mod prediction_market {
             PredictionError::EventEnded
         );
         require!(
-            clock.unix_timestamp < event_deadline,
-            PredictionError::EventEnded
+            clock.unix_timestamp < betting_closes_at,
+            PredictionError::BettingClosed
         );
+        ctx.accounts.event.validate_outcome(outcome)?;
+        let bet_limits = ctx.accounts.event.bet_limits;
+        bet_limits.check_bet(amount, ctx.accounts.user_bet.total_stake())?;
+
+        // LMSR shares are priced off the outstanding shares and paid in DOOM
+        let cost = if ctx.accounts.event.pricing == PricingMode::Lmsr {
+            let event = &ctx.accounts.event;
+            let (doom_shares, life_shares) = match outcome {
+                Outcome::Doom => (
+                    event.doom_pool.checked_add(amount).ok_or(PredictionError::Overflow)?,
+                    event.life_pool,
+                ),
+                Outcome::Life => (
+                    event.doom_pool,
+                    event.life_pool.checked_add(amount).ok_or(PredictionError::Overflow)?,
+                ),
+                _ => return err!(PredictionError::InvalidOutcome),
+            };
+            let price = lmsr_price(
+                (event.doom_pool, event.life_pool),
+                (doom_shares, life_shares),
+                event.lmsr_liquidity,
+            )?;
+            if let Some(max_cost) = max_cost {
+                require!(price <= max_cost, PredictionError::MaxCostExceeded);
+            }
+            Some(price)
+        } else {
+            None
+        };
+
+        // Escrow the stake in the vault for the chosen outcome
+        let (from, to) = match outcome {
+            Outcome::Life if cost.is_some() => (
+                ctx.accounts.user_doom_account.to_account_info(),
+                ctx.accounts.doom_vault.to_account_info(),
+            ),
+            Outcome::Doom => (
+                ctx.accounts.user_doom_account.to_account_info(),
+                ctx.accounts.doom_vault.to_account_info(),
+            ),
+            Outcome::Life => (
+                ctx.accounts.user_life_account.to_account_info(),
+                ctx.accounts.life_vault.to_account_info(),
+            ),
+            Outcome::Categorical(index) => {
+                let event_outcomes = ctx
+                    .accounts
+                    .event_outcomes
+                    .as_ref()
+                    .ok_or(PredictionError::MissingEventOutcomes)?;
+                let outcome_vault = ctx
+                    .accounts
+                    .outcome_vault
+                    .as_ref()
+                    .ok_or(PredictionError::InvalidOutcomeVault)?;
+                require_keys_eq!(
+                    outcome_vault.key(),
+                    event_outcomes.vault_address(&event_key, index)?,
+                    PredictionError::InvalidOutcomeVault
+                );
+                (
+                    ctx.accounts.user_doom_account.to_account_info(),
+                    outcome_vault.to_account_info(),
+                )
+            }
+            Outcome::Scalar(_) | Outcome::Invalid | Outcome::Draw => {
+                return err!(PredictionError::InvalidOutcome)
+            }
+        };
+        let transfer = Transfer {
+            from,
+            to,
+            authority: ctx.accounts.user.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer),
+            cost.unwrap_or(amount),
+        )?;
+
+        // Tokenized events issue shares 1:1 with the stake
+        if ctx.accounts.event.tokenized {
+            let share_mint = ctx
+                .accounts
+                .share_mint
+                .as_ref()
+                .ok_or(PredictionError::InvalidShareMint)?;
+            let user_share_account = ctx
+                .accounts
+                .user_share_account
+                .as_ref()
+                .ok_or(PredictionError::InvalidShareMint)?;
+            require_keys_eq!(
+                share_mint.key(),
+                ctx.accounts.event.share_mint_address(&event_key, outcome)?,
+                PredictionError::InvalidShareMint
+            );
+            require_keys_eq!(
+                user_share_account.mint,
+                share_mint.key(),
+                PredictionError::InvalidShareMint
+            );
+
+            let event_id_bytes = event_id.to_le_bytes();
+            let seeds = &[b"event".as_ref(), event_id_bytes.as_ref(), &[ctx.accounts.event.bump]];
+            let signer_seeds = &[&seeds[..]];
+            let mint_to = MintTo {
+                mint: share_mint.to_account_info(),
+                to: user_share_account.to_account_info(),
+                authority: ctx.accounts.event.to_account_info(),
+            };
+            token::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    mint_to,
+                    signer_seeds,
+                ),
+                amount,
+            )?;
+        }
 
         // Update event pools
         let event = &mut ctx.accounts.event;
-        match outcome {
+        let (side_pool, total_pool) = match outcome {
             Outcome::Doom => {
                 event.doom_pool = event.doom_pool.checked_add(amount).ok_or(PredictionError::Overflow)?;
+                (event.doom_pool, event.doom_pool.saturating_add(event.life_pool))
             }
             Outcome::Life => {
                 event.life_pool = event.life_pool.checked_add(amount).ok_or(PredictionError::Overflow)?;
+                (event.life_pool, event.doom_pool.saturating_add(event.life_pool))
+            }
+            Outcome::Categorical(index) => {
+                let event_outcomes = ctx
+                    .accounts
+                    .event_outcomes
+                    .as_mut()
+                    .ok_or(PredictionError::MissingEventOutcomes)?;
+                let pool = &mut event_outcomes.pools[index as usize];
+                *pool = pool.checked_add(amount).ok_or(PredictionError::Overflow)?;
+                (*pool, event_outcomes.total_pool())
             }
+            Outcome::Scalar(_) | Outcome::Invalid | Outcome::Draw => {
+                return err!(PredictionError::InvalidOutcome)
+            }
+        };
+        // The first bet seeds the pool and always holds all of it
+        if total_pool > amount {
+            bet_limits.check_side_share(side_pool, total_pool)?;
         }
-        event.total_bettors = event.total_bettors.saturating_add(1);
 
-        // Record user bet
+        // Record user bet, creating the position on the first bet
         let user_bet = &mut ctx.accounts.user_bet;
-        user_bet.event = event_key;
-        user_bet.user = user_key;
-        user_bet.outcome = outcome;
-        user_bet.amount = amount;
-        user_bet.placed_at = clock.unix_timestamp;
-        user_bet.claimed = false;
-        user_bet.refunded = false;
-        user_bet.bump = ctx.bumps.user_bet;
+        if user_bet.user == Pubkey::default() {
+            user_bet.event = event_key;
+            user_bet.user = user_key;
+            user_bet.placed_at = clock.unix_timestamp;
+            user_bet.claimed = false;
+            user_bet.refunded = false;
+            user_bet.settled_loss = false;
+            user_bet.bump = ctx.bumps.user_bet;
+            event.total_bettors = event.total_bettors.saturating_add(1);
+            event.outstanding_bets = event.outstanding_bets.saturating_add(1);
+        }
+        user_bet.add_stake(outcome, amount, event.outcome_count)?;
+        if let Some(cost) = cost {
+            user_bet.lmsr_cost = user_bet.lmsr_cost.checked_add(cost).ok_or(PredictionError::Overflow)?;
+            event.lmsr_collected = event.lmsr_collected.checked_add(cost).ok_or(PredictionError::Overflow)?;
+            msg!("Bought {} shares for {} DOOM", amount, cost);
+        }
 
         let platform_config = &mut ctx.accounts.platform_config;
         platform_config.total_bets = platform_config.total_bets.saturating_add(1);
 
+        let user_stats = &mut ctx.accounts.user_stats;
+        user_stats.init_if_new(user_key, ctx.bumps.user_stats);
+        user_stats.total_bets = user_stats.total_bets.saturating_add(1);
+        user_stats.total_wagered = user_stats.total_wagered.saturating_add(cost.unwrap_or(amount));
+        user_stats.first_bet_at = user_stats.first_bet_at.or(Some(clock.unix_timestamp));
+        user_stats.last_bet_at = Some(clock.unix_timestamp);
+
         msg!("Bet placed: {} on {:?} for event {}", amount, outcome, event_id);
         Ok(())
     }
 
-    /// Resolve an event with the final outcome
+    /// Exit part or all of a position before betting closes
+    ///
+    /// The early-exit penalty goes to the event's penalty pool, which is paid
+    /// out pro-rata to the winners, or to every bettor on a refund, and counts
+    /// as lost in the user's stats. Exiting the whole position closes the bet
+    /// account.
+    pub fn withdraw_bet(ctx: Context<WithdrawBet>, outcome: Outcome, amount: u64) -> Result<()> {
+        require!(amount > 0, PredictionError::InvalidBetAmount);
+
+        let clock = Clock::get()?;
+        let event = &ctx.accounts.event;
+        require!(!event.tokenized, PredictionError::EventTokenized);
+        require!(
+            event.pricing == PricingMode::Parimutuel,
+            PredictionError::LmsrUnsupported
+        );
+        require!(
+            event.status == EventStatus::Active,
+            PredictionError::EventEnded
+        );
+        require!(
+            clock.unix_timestamp < event.betting_closes_at,
+            PredictionError::BettingClosed
+        );
+        event.validate_outcome(outcome)?;
+        ctx.accounts.user_bet.remove_stake(outcome, amount)?;
+
+        let penalty = fee_for(amount, event.exit_penalty_bps)?;
+        let refund = amount - penalty;
+
+        let (vault, to) = match outcome {
+            Outcome::Doom => (
+                ctx.accounts.doom_vault.to_account_info(),
+                ctx.accounts.user_doom_account.to_account_info(),
+            ),
+            Outcome::Life => (
+                ctx.accounts.life_vault.to_account_info(),
+                ctx.accounts.user_life_account.to_account_info(),
+            ),
+            Outcome::Categorical(index) => {
+                let event_outcomes = ctx
+                    .accounts
+                    .event_outcomes
+                    .as_ref()
+                    .ok_or(PredictionError::MissingEventOutcomes)?;
+                let outcome_vault = ctx
+                    .accounts
+                    .outcome_vault
+                    .as_ref()
+                    .ok_or(PredictionError::InvalidOutcomeVault)?;
+                require_keys_eq!(
+                    outcome_vault.key(),
+                    event_outcomes.vault_address(&event.key(), index)?,
+                    PredictionError::InvalidOutcomeVault
+                );
+                (
+                    outcome_vault.to_account_info(),
+                    ctx.accounts.user_doom_account.to_account_info(),
+                )
+            }
+            Outcome::Scalar(_) | Outcome::Invalid | Outcome::Draw => {
+                return err!(PredictionError::InvalidOutcome)
+            }
+        };
+        transfer_from_vault(&ctx.accounts.token_program, vault.clone(), to, event, refund)?;
+        // Categorical penalties move to the DOOM vault, next to the binary ones
+        if matches!(outcome, Outcome::Categorical(_)) {
+            transfer_from_vault(
+                &ctx.accounts.token_program,
+                vault,
+                ctx.accounts.doom_vault.to_account_info(),
+                event,
+                penalty,
+            )?;
+        }
+
+        // The whole amount leaves the pool; the penalty goes to the winners
+        let event = &mut ctx.accounts.event;
+        match outcome {
+            Outcome::Doom => {
+                event.doom_pool = event.doom_pool.checked_sub(amount).ok_or(PredictionError::Overflow)?;
+                event.doom_penalty_pool =
+                    event.doom_penalty_pool.checked_add(penalty).ok_or(PredictionError::Overflow)?;
+            }
+            Outcome::Life => {
+                event.life_pool = event.life_pool.checked_sub(amount).ok_or(PredictionError::Overflow)?;
+                event.life_penalty_pool =
+                    event.life_penalty_pool.checked_add(penalty).ok_or(PredictionError::Overflow)?;
+            }
+            Outcome::Categorical(index) => {
+                let event_outcomes = ctx
+                    .accounts
+                    .event_outcomes
+                    .as_mut()
+                    .ok_or(PredictionError::MissingEventOutcomes)?;
+                let pool = &mut event_outcomes.pools[index as usize];
+                *pool = pool.checked_sub(amount).ok_or(PredictionError::Overflow)?;
+                event.doom_penalty_pool =
+                    event.doom_penalty_pool.checked_add(penalty).ok_or(PredictionError::Overflow)?;
+            }
+            Outcome::Scalar(_) | Outcome::Invalid | Outcome::Draw => {
+                return err!(PredictionError::InvalidOutcome)
+            }
+        }
+
+        // The withdrawn stake no longer counts as wagered; the penalty is lost
+        let user_stats = &mut ctx.accounts.user_stats;
+        user_stats.total_wagered = user_stats.total_wagered.saturating_sub(amount);
+        user_stats.record_penalty(penalty);
+
+        if ctx.accounts.user_bet.total_stake() == 0 {
+            event.total_bettors = event.total_bettors.saturating_sub(1);
+            event.outstanding_bets = event.outstanding_bets.saturating_sub(1);
+            user_stats.total_bets = user_stats.total_bets.saturating_sub(1);
+            ctx.accounts.user_bet.close(ctx.accounts.user.to_account_info())?;
+        }
+
+        msg!(
+            "Withdrew {} from {:?} on event {}, {} added to the penalty pool",
+            amount,
+            outcome,
+            event.event_id,
+            penalty
+        );
+        Ok(())
+    }
+
+    /// Close betting on an event once its betting cutoff has passed
+    ///
+    /// Permissionless crank; resolution does not require it.
+    pub fn close_betting(ctx: Context<CloseBetting>) -> Result<()> {
+        let clock = Clock::get()?;
+        let event = &mut ctx.accounts.event;
+
+        require!(
+            clock.unix_timestamp >= event.betting_closes_at,
+            PredictionError::DeadlineNotReached
+        );
+
+        event.status = event.status.transition(EventStatus::Closed)?;
+
+        msg!("Betting closed on event {}", event.event_id);
+        Ok(())
+    }
+
+    /// Propose the final outcome of an event
+    ///
+    /// The oracle posts a DOOM bond and the proposal only becomes final after
+    /// the platform's dispute window. With no dispute window configured the
+    /// event resolves immediately. Scalar events resolve to `Outcome::Scalar`
+    /// with the observed value.
     pub fn resolve_event(
         ctx: Context<ResolveEvent>,
         outcome: Outcome,
     ) -> Result<()> {
         let clock = Clock::get()?;
+        let dispute_window = ctx.accounts.platform_config.dispute_window;
+        let bond = ctx.accounts.platform_config.dispute_bond;
         let event = &mut ctx.accounts.event;
 
-        require!(
-            event.status == EventStatus::Active,
-            PredictionError::EventAlreadyResolved
-        );
         require!(
             clock.unix_timestamp >= event.deadline,
             PredictionError::EventNotResolved
         );
+        require!(
+            clock.unix_timestamp <= event.resolution_deadline,
+            PredictionError::ResolutionDeadlinePassed
+        );
 
-        event.status = EventStatus::Resolved;
+        event.validate_resolution(outcome)?;
         event.outcome = Some(outcome);
+
+        if dispute_window == 0 {
+            event.status = event.status.transition(EventStatus::Resolved)?;
+            event.resolved_at = Some(clock.unix_timestamp);
+
+            msg!("Event {} resolved with outcome: {:?}", event.event_id, outcome);
+            return Ok(());
+        }
+
+        event.status = event.status.transition(EventStatus::Proposed)?;
+        event.proposer = ctx.accounts.oracle.key();
+        event.dispute_ends_at = clock
+            .unix_timestamp
+            .checked_add(dispute_window)
+            .ok_or(PredictionError::Overflow)?;
+        event.bond = bond;
+
+        let transfer = Transfer {
+            from: ctx.accounts.proposer_doom_account.to_account_info(),
+            to: ctx.accounts.bond_vault.to_account_info(),
+            authority: ctx.accounts.oracle.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer),
+            bond,
+        )?;
+
+        msg!(
+            "Outcome {:?} proposed for event {}, disputable until {}",
+            outcome,
+            event.event_id,
+            event.dispute_ends_at
+        );
+        Ok(())
+    }
+
+    /// Dispute a proposed outcome by matching the proposer's DOOM bond
+    ///
+    /// Escalates the event to the platform authority, see `settle_dispute`.
+    pub fn dispute_resolution(ctx: Context<DisputeResolution>) -> Result<()> {
+        let clock = Clock::get()?;
+        let event = &mut ctx.accounts.event;
+
+        require!(
+            event.status == EventStatus::Proposed,
+            PredictionError::NoResolutionProposed
+        );
+        require!(
+            clock.unix_timestamp < event.dispute_ends_at,
+            PredictionError::DisputeWindowClosed
+        );
+
+        event.status = event.status.transition(EventStatus::Disputed)?;
+        event.disputer = Some(ctx.accounts.disputer.key());
+
+        let transfer = Transfer {
+            from: ctx.accounts.disputer_doom_account.to_account_info(),
+            to: ctx.accounts.bond_vault.to_account_info(),
+            authority: ctx.accounts.disputer.to_account_info(),
+        };
+        token::transfer(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer),
+            event.bond,
+        )?;
+
+        msg!("Resolution of event {} disputed", event.event_id);
+        Ok(())
+    }
+
+    /// Finalize an undisputed proposal once its dispute window has passed
+    ///
+    /// Permissionless; returns the proposer's bond.
+    pub fn finalize_resolution(ctx: Context<FinalizeResolution>) -> Result<()> {
+        let clock = Clock::get()?;
+        let event = &mut ctx.accounts.event;
+
+        require!(
+            event.status == EventStatus::Proposed,
+            PredictionError::NoResolutionProposed
+        );
+        require!(
+            clock.unix_timestamp >= event.dispute_ends_at,
+            PredictionError::DisputeWindowOpen
+        );
+
+        event.status = event.status.transition(EventStatus::Resolved)?;
         event.resolved_at = Some(clock.unix_timestamp);
 
-        msg!("Event {} resolved with outcome: {:?}", event.event_id, outcome);
+        transfer_from_platform_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.bond_vault,
+            ctx.accounts.proposer_doom_account.to_account_info(),
+            &ctx.accounts.platform_config,
+            event.bond,
+        )?;
+
+        msg!("Event {} resolved with outcome: {:?}", event.event_id, event.outcome);
         Ok(())
     }
 
-    /// Cancel an event
-    pub fn cancel_event(ctx: Context<CancelEvent>) -> Result<()> {
+    /// Settle a disputed resolution with the authority's ruling
+    ///
+    /// Whichever of the proposer and disputer was wrong forfeits their bond
+    /// to the other.
+    pub fn settle_dispute(ctx: Context<SettleDispute>, outcome: Outcome) -> Result<()> {
+        let clock = Clock::get()?;
         let event = &mut ctx.accounts.event;
-        event.status = EventStatus::Cancelled;
-        msg!("Event {} cancelled", event.event_id);
+
+        require!(
+            event.status == EventStatus::Disputed,
+            PredictionError::EventNotDisputed
+        );
+        event.validate_resolution(outcome)?;
+
+        let proposal_upheld = event.outcome == Some(outcome);
+        // Disputes from a split oracle committee carry no bonds
+        if event.bond > 0 {
+            let winner = if proposal_upheld {
+                event.proposer
+            } else {
+                event.disputer.ok_or(PredictionError::EventNotDisputed)?
+            };
+            require_keys_eq!(
+                ctx.accounts.winner_doom_account.owner,
+                winner,
+                PredictionError::InvalidBondAccount
+            );
+        }
+
+        event.status = event.status.transition(EventStatus::Resolved)?;
+        event.outcome = Some(outcome);
+        event.resolved_at = Some(clock.unix_timestamp);
+
+        let bonds = event.bond.checked_mul(2).ok_or(PredictionError::Overflow)?;
+        transfer_from_platform_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.bond_vault,
+            ctx.accounts.winner_doom_account.to_account_info(),
+            &ctx.accounts.platform_config,
+            bonds,
+        )?;
+
+        msg!(
+            "Dispute on event {} settled with outcome {:?}, proposal {}",
+            event.event_id,
+            outcome,
+            if proposal_upheld { "upheld" } else { "overturned" }
+        );
         Ok(())
     }
 
-    /// Update platform configuration
-    pub fn update_platform(
-        ctx: Context<UpdatePlatform>,
-        fee_basis_points: Option<u16>,
-        new_oracle: Option<Pubkey>,
-        paused: Option<bool>,
+    /// Add or remove a resolver that events can be bound to at creation
+    pub fn update_resolver_allowlist(
+        ctx: Context<UpdateResolverAllowlist>,
+        resolver: Pubkey,
+        allowed: bool,
     ) -> Result<()> {
-        let config = &mut ctx.accounts.platform_config;
+        let allowlist = &mut ctx.accounts.resolver_allowlist;
+        allowlist.bump = ctx.bumps.resolver_allowlist;
 
-        if let Some(fee) = fee_basis_points {
-            require!(fee <= 10000, PredictionError::InvalidFeeBasisPoints);
-            config.fee_basis_points = fee;
-        }
-        if let Some(oracle) = new_oracle {
-            config.oracle = oracle;
-        }
-        if let Some(is_paused) = paused {
-            config.paused = is_paused;
+        let position = allowlist.resolvers.iter().position(|r| *r == resolver);
+        match (allowed, position) {
+            (true, None) => {
+                require!(
+                    allowlist.resolvers.len() < MAX_ALLOWED_RESOLVERS,
+                    PredictionError::ResolverAllowlistFull
+                );
+                allowlist.resolvers.push(resolver);
+            }
+            (false, Some(index)) => {
+                allowlist.resolvers.swap_remove(index);
+            }
+            _ => {}
         }
 
+        msg!("Resolver {} allowed: {}", resolver, allowed);
         Ok(())
     }
-}
-
-// Account Contexts
-#[derive(Accounts)]
-pub struct InitializePlatform<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + PlatformConfig::INIT_SPACE,
-        seeds = [b"platform_config"],
-        bump
-    )]
-    pub platform_config: Account<'info, PlatformConfig>,
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
-}
 
-#[derive(Accounts)]
-#[instruction(event_id: u64)]
+    /// Set the oracle committee members and how many must agree on an outcome
+    pub fn set_oracle_committee(
+        ctx: Context<SetOracleCommittee>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            !members.is_empty() && members.len() <= MAX_COMMITTEE_MEMBERS,
+            PredictionError::InvalidCommittee
+        );
+        require!(
+            threshold > 0 && threshold as usize <= members.len(),
+            PredictionError::InvalidCommittee
+        );
+        require!(
+            members
+                .iter()
+                .enumerate()
+                .all(|(i, member)| !members[..i].contains(member)),
+            PredictionError::InvalidCommittee
+        );
+
+        let committee = &mut ctx.accounts.oracle_committee;
+        committee.members = members;
+        committee.threshold = threshold;
+        committee.bump = ctx.bumps.oracle_committee;
+
+        msg!(
+            "Oracle committee set: {} of {}",
+            threshold,
+            committee.members.len()
+        );
+        Ok(())
+    }
+
+    /// Vote on the outcome of an event as an oracle committee member
+    ///
+    /// The event resolves once `threshold` members agree. A vote for a
+    /// different outcome than earlier votes marks the event disputed, to be
+    /// settled by the authority with `settle_dispute`. Events bound to their
+    /// own resolver are left to that resolver.
+    pub fn submit_oracle_vote(
+        ctx: Context<SubmitOracleVote>,
+        outcome: Outcome,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let member = ctx.accounts.member.key();
+        let committee = &ctx.accounts.oracle_committee;
+        require!(
+            committee.members.contains(&member),
+            PredictionError::NotCommitteeMember
+        );
+        let threshold = committee.threshold;
+
+        let event = &mut ctx.accounts.event;
+        require!(
+            clock.unix_timestamp >= event.deadline,
+            PredictionError::EventNotResolved
+        );
+        require!(
+            clock.unix_timestamp <= event.resolution_deadline,
+            PredictionError::ResolutionDeadlinePassed
+        );
+        require!(
+            matches!(event.status, EventStatus::Active | EventStatus::Closed),
+            PredictionError::EventNotOpenForVotes
+        );
+        event.validate_resolution(outcome)?;
+
+        let vote = &mut ctx.accounts.oracle_vote;
+        vote.event = event.key();
+        vote.member = member;
+        vote.outcome = outcome;
+        vote.voted_at = clock.unix_timestamp;
+        vote.bump = ctx.bumps.oracle_vote;
+        event.oracle_votes = event.oracle_votes.saturating_add(1);
+
+        if matches!(event.committee_outcome, Some(agreed) if agreed != outcome) {
+            event.status = event.status.transition(EventStatus::Disputed)?;
+            msg!("Conflicting oracle votes on event {}", event.event_id);
+            return Ok(());
+        }
+
+        event.committee_outcome = Some(outcome);
+        event.committee_votes = event.committee_votes.saturating_add(1);
+
+        if event.committee_votes >= threshold {
+            event.status = event.status.transition(EventStatus::Resolved)?;
+            event.outcome = Some(outcome);
+            event.resolved_at = Some(clock.unix_timestamp);
+
+            msg!("Event {} resolved by committee with outcome: {:?}", event.event_id, outcome);
+        } else {
+            msg!(
+                "Oracle vote {:?} on event {} ({} of {})",
+                outcome,
+                event.event_id,
+                event.committee_votes,
+                threshold
+            );
+        }
+        Ok(())
+    }
+
+    /// Close an oracle vote once its event is no longer open for votes,
+    /// returning its rent to the member
+    ///
+    /// Permissionless.
+    pub fn close_oracle_vote(ctx: Context<CloseOracleVote>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        require!(
+            !matches!(event.status, EventStatus::Active | EventStatus::Closed),
+            PredictionError::VotingOpen
+        );
+
+        event.oracle_votes = event.oracle_votes.saturating_sub(1);
+        let event_id = event.event_id;
+        close_swept_event(&ctx.accounts.event, &ctx.accounts.creator)?;
+
+        msg!("Closed oracle vote of {} on event {}", ctx.accounts.member.key(), event_id);
+        Ok(())
+    }
+
+    /// Resolve a price-triggered event from its price account
+    ///
+    /// Permissionless. The price must be published at or after the deadline
+    /// and not after the current time, be fresh and have a tight enough
+    /// confidence interval.
+    pub fn resolve_from_price_feed(ctx: Context<ResolveFromPriceFeed>) -> Result<()> {
+        let clock = Clock::get()?;
+        let price = load_price_feed(&ctx.accounts.price_feed)?;
+        let event = &mut ctx.accounts.event;
+
+        require!(
+            event.price_feed == Some(ctx.accounts.price_feed.key()),
+            PredictionError::PriceFeedMismatch
+        );
+        require!(event.kind == EventKind::Binary, PredictionError::InvalidOutcome);
+        require!(
+            price.expo == event.price_expo,
+            PredictionError::PriceFeedMismatch
+        );
+        require!(
+            clock.unix_timestamp >= event.deadline,
+            PredictionError::EventNotResolved
+        );
+        require!(
+            clock.unix_timestamp <= event.resolution_deadline,
+            PredictionError::ResolutionDeadlinePassed
+        );
+        require!(
+            price.publish_time <= clock.unix_timestamp,
+            PredictionError::PriceInFuture
+        );
+        require!(
+            clock.unix_timestamp.saturating_sub(price.publish_time) <= MAX_PRICE_AGE,
+            PredictionError::StalePrice
+        );
+        require!(
+            price.publish_time >= event.deadline,
+            PredictionError::PriceBeforeDeadline
+        );
+        require!(
+            price.price > 0
+                && (price.conf as u128) * 10000
+                    <= (price.price as u128) * MAX_PRICE_CONFIDENCE_BPS as u128,
+            PredictionError::PriceConfidenceTooWide
+        );
+
+        let triggered = match event.price_comparator {
+            PriceComparator::Below => price.price < event.price_threshold,
+            PriceComparator::Above => price.price > event.price_threshold,
+        };
+        let outcome = if triggered { Outcome::Doom } else { Outcome::Life };
+
+        event.status = event.status.transition(EventStatus::Resolved)?;
+        event.outcome = Some(outcome);
+        event.resolved_at = Some(clock.unix_timestamp);
+
+        msg!(
+            "Event {} resolved from price {}e{} with outcome: {:?}",
+            event.event_id,
+            price.price,
+            price.expo,
+            outcome
+        );
+        Ok(())
+    }
+
+    /// Expire an event the oracle failed to resolve by its resolution deadline
+    ///
+    /// Permissionless so that anyone can unlock refunds for stuck funds. A
+    /// dispute nobody settled expires a dispute window after the resolution
+    /// deadline, refunding both bonds.
+    pub fn expire_event(ctx: Context<ExpireEvent>) -> Result<()> {
+        let clock = Clock::get()?;
+        let event = &mut ctx.accounts.event;
+
+        let disputed = event.status == EventStatus::Disputed;
+        let expires_at = if disputed {
+            event
+                .resolution_deadline
+                .checked_add(ctx.accounts.platform_config.dispute_window)
+                .ok_or(PredictionError::Overflow)?
+        } else {
+            event.resolution_deadline
+        };
+        require!(
+            clock.unix_timestamp > expires_at,
+            PredictionError::ResolutionDeadlineNotPassed
+        );
+
+        event.status = event.status.transition(EventStatus::Expired)?;
+        let event_id = event.event_id;
+        let bond = event.bond;
+
+        // Disputes from a split oracle committee carry no bonds
+        if disputed && bond > 0 {
+            let accounts = &ctx.accounts;
+            let bond_vault = accounts
+                .bond_vault
+                .as_ref()
+                .ok_or(PredictionError::DisputeBondAccountsRequired)?;
+            let proposer_doom_account = accounts
+                .proposer_doom_account
+                .as_ref()
+                .ok_or(PredictionError::DisputeBondAccountsRequired)?;
+            let disputer_doom_account = accounts
+                .disputer_doom_account
+                .as_ref()
+                .ok_or(PredictionError::DisputeBondAccountsRequired)?;
+            let token_program = accounts
+                .token_program
+                .as_ref()
+                .ok_or(PredictionError::DisputeBondAccountsRequired)?;
+
+            for to in [proposer_doom_account, disputer_doom_account] {
+                transfer_from_platform_vault(
+                    token_program,
+                    bond_vault,
+                    to.to_account_info(),
+                    &accounts.platform_config,
+                    bond,
+                )?;
+            }
+        }
+
+        msg!("Event {} expired unresolved", event_id);
+        Ok(())
+    }
+
+    /// Claim parimutuel winnings from a resolved event
+    ///
+    /// Winners get their stake back plus a pro-rata share of the losing pool
+    /// and of the exit penalties, less the platform fee which is taken from
+    /// the share. Categorical events
+    /// pass every outcome vault, in outcome order, as remaining accounts.
+    pub fn claim_winnings<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimWinnings<'info>>,
+    ) -> Result<()> {
+        let event = &ctx.accounts.event;
+        let user_bet = &ctx.accounts.user_bet;
+
+        require!(!event.tokenized, PredictionError::EventTokenized);
+        require!(!user_bet.claimed, PredictionError::AlreadyClaimed);
+        require!(
+            event.status == EventStatus::Resolved,
+            PredictionError::EventNotResolved
+        );
+
+        let winning_outcome = event.outcome.ok_or(PredictionError::EventNotResolved)?;
+        require!(
+            winning_outcome != Outcome::Invalid,
+            PredictionError::EventResolvedInvalid
+        );
+        require!(
+            event.claimable_stake(user_bet) > 0,
+            PredictionError::NotAWinner
+        );
+
+        let total_stake = user_bet.wagered();
+        let fee_basis_points = event.fee_basis_points;
+
+        let mut payout = match winning_outcome {
+            _ if event.pricing == PricingMode::Lmsr => {
+                pay_lmsr_winnings(ctx.accounts, winning_outcome, fee_basis_points)?
+            }
+            Outcome::Categorical(index) => pay_categorical_winnings(
+                ctx.accounts,
+                ctx.remaining_accounts,
+                index,
+                fee_basis_points,
+            )?,
+            Outcome::Scalar(value) => pay_scalar_winnings(ctx.accounts, value, fee_basis_points)?,
+            Outcome::Draw => pay_draw(ctx.accounts, ctx.remaining_accounts, fee_basis_points)?,
+            _ => pay_binary_winnings(ctx.accounts, winning_outcome, fee_basis_points)?,
+        };
+        pay_penalty_shares(ctx.accounts, &mut payout, fee_basis_points)?;
+
+        let event = &mut ctx.accounts.event;
+        event.settled_stake = event
+            .settled_stake
+            .checked_add(payout.stake)
+            .ok_or(PredictionError::Overflow)?;
+        if event.settled_stake == payout.winning_pool {
+            event.status = event.status.transition(EventStatus::Settled)?;
+        }
+        event.creator_doom_fees = event
+            .creator_doom_fees
+            .checked_add(payout.creator_doom_fee)
+            .ok_or(PredictionError::Overflow)?;
+        event.creator_life_fees = event
+            .creator_life_fees
+            .checked_add(payout.creator_life_fee)
+            .ok_or(PredictionError::Overflow)?;
+        let event_id = event.event_id;
+
+        let platform_config = &mut ctx.accounts.platform_config;
+        platform_config.total_doom_fees = platform_config.total_doom_fees.saturating_add(payout.doom_fee);
+        platform_config.total_life_fees = platform_config.total_life_fees.saturating_add(payout.life_fee);
+
+        let user_bet = &mut ctx.accounts.user_bet;
+        user_bet.claimed = true;
+
+        // Any hedge on the losing side is forfeit; draws are neither a win nor a loss
+        let received = payout.returned.saturating_add(payout.winnings);
+        if winning_outcome != Outcome::Draw {
+            let user_stats = &mut ctx.accounts.user_stats;
+            if received >= total_stake {
+                user_stats.record_win(received - total_stake);
+            } else {
+                user_stats.record_loss(total_stake - received);
+            }
+        }
+
+        msg!(
+            "Claimed {} stake + {} winnings ({} DOOM / {} LIFE fee) on event {}",
+            payout.returned,
+            payout.winnings,
+            payout.doom_fee,
+            payout.life_fee,
+            event_id
+        );
+        Ok(())
+    }
+
+    /// Settle a losing bet on a resolved event into the bettor's stats
+    ///
+    /// Permissionless so losses can't be hidden by simply never settling.
+    pub fn settle_losing_bet(ctx: Context<SettleLosingBet>) -> Result<()> {
+        let event = &ctx.accounts.event;
+        let user_bet = &ctx.accounts.user_bet;
+
+        require!(!user_bet.claimed, PredictionError::AlreadyClaimed);
+        require!(!user_bet.settled_loss, PredictionError::LossAlreadySettled);
+        require!(
+            matches!(event.status, EventStatus::Resolved | EventStatus::Settled),
+            PredictionError::EventNotResolved
+        );
+
+        require!(
+            event.outcome != Some(Outcome::Invalid) && event.claimable_stake(user_bet) == 0,
+            PredictionError::BetNotLost
+        );
+
+        let stake = user_bet.wagered();
+        let event_id = event.event_id;
+
+        let user_bet = &mut ctx.accounts.user_bet;
+        user_bet.settled_loss = true;
+
+        ctx.accounts.user_stats.record_loss(stake);
+
+        msg!("Settled losing bet of {} on event {}", stake, event_id);
+        Ok(())
+    }
+
+    /// Cancel an event
+    pub fn cancel_event(ctx: Context<CancelEvent>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.status = event.status.transition(EventStatus::Cancelled)?;
+        msg!("Event {} cancelled", event.event_id);
+        Ok(())
+    }
+
+    /// Cancel a spam or abusive event, slashing the creator's bond to the treasury
+    pub fn cancel_event_for_abuse(ctx: Context<CancelEventForAbuse>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.status = event.status.transition(EventStatus::Cancelled)?;
+
+        let slashed = event.creation_bond;
+        event.creation_bond = 0;
+
+        transfer_from_platform_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.bond_vault,
+            ctx.accounts.doom_treasury.to_account_info(),
+            &ctx.accounts.platform_config,
+            slashed,
+        )?;
+
+        let platform_config = &mut ctx.accounts.platform_config;
+        platform_config.total_doom_fees = platform_config.total_doom_fees.saturating_add(slashed);
+
+        msg!("Event {} cancelled for abuse, {} bond slashed", event.event_id, slashed);
+        Ok(())
+    }
+
+    /// Return the creator's bond once the event is resolved, cancelled or expired
+    ///
+    /// Permissionless. Bonds slashed by `cancel_event_for_abuse` are gone.
+    pub fn return_creation_bond(ctx: Context<ReturnCreationBond>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+
+        require!(event.creation_bond > 0, PredictionError::NoCreationBond);
+        require!(
+            matches!(
+                event.status,
+                EventStatus::Resolved
+                    | EventStatus::Cancelled
+                    | EventStatus::Expired
+                    | EventStatus::Settled
+            ),
+            PredictionError::EventNotFinalized
+        );
+
+        let bond = event.creation_bond;
+        event.creation_bond = 0;
+
+        transfer_from_platform_vault(
+            &ctx.accounts.token_program,
+            &ctx.accounts.bond_vault,
+            ctx.accounts.creator_doom_account.to_account_info(),
+            &ctx.accounts.platform_config,
+            bond,
+        )?;
+
+        msg!("Returned {} creation bond on event {}", bond, event.event_id);
+        Ok(())
+    }
+
+    /// Withdraw the creator's cut of the fees taken on an event's claims
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        let event = &ctx.accounts.event;
+        let doom_fees = event.creator_doom_fees;
+        let life_fees = event.creator_life_fees;
+
+        require!(doom_fees > 0 || life_fees > 0, PredictionError::NoCreatorFees);
+
+        let accounts = &ctx.accounts;
+        transfer_from_vault(
+            &accounts.token_program,
+            accounts.doom_vault.to_account_info(),
+            accounts.creator_doom_account.to_account_info(),
+            event,
+            doom_fees,
+        )?;
+        transfer_from_vault(
+            &accounts.token_program,
+            accounts.life_vault.to_account_info(),
+            accounts.creator_life_account.to_account_info(),
+            event,
+            life_fees,
+        )?;
+
+        let event = &mut ctx.accounts.event;
+        event.creator_doom_fees = 0;
+        event.creator_life_fees = 0;
+
+        msg!(
+            "Creator claimed {} DOOM / {} LIFE fees on event {}",
+            doom_fees,
+            life_fees,
+            event.event_id
+        );
+        Ok(())
+    }
+
+    /// Refund the full escrowed stake of a bet on a cancelled or expired event
+    ///
+    /// The bet also gets its pro-rata share of any exit penalties. Categorical
+    /// events pass every outcome vault as remaining accounts.
+    pub fn claim_refund<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimRefund<'info>>,
+    ) -> Result<()> {
+        let event = &ctx.accounts.event;
+        let user_bet = &ctx.accounts.user_bet;
+
+        require!(!event.tokenized, PredictionError::EventTokenized);
+        require!(!user_bet.refunded, PredictionError::AlreadyRefunded);
+        require!(
+            matches!(event.status, EventStatus::Cancelled | EventStatus::Expired)
+                || (event.status == EventStatus::Resolved
+                    && event.outcome == Some(Outcome::Invalid)),
+            PredictionError::EventNotRefundable
+        );
+
+        let amount = user_bet.wagered();
+        let accounts = &ctx.accounts;
+        let total_pool = match event.kind {
+            EventKind::Binary if event.pricing == PricingMode::Lmsr => {
+                transfer_from_vault(
+                    &accounts.token_program,
+                    accounts.doom_vault.to_account_info(),
+                    accounts.user_doom_account.to_account_info(),
+                    event,
+                    user_bet.lmsr_cost,
+                )?;
+                event.lmsr_collected
+            }
+            EventKind::Binary | EventKind::Scalar => {
+                transfer_from_vault(
+                    &accounts.token_program,
+                    accounts.doom_vault.to_account_info(),
+                    accounts.user_doom_account.to_account_info(),
+                    event,
+                    user_bet.doom_amount,
+                )?;
+                transfer_from_vault(
+                    &accounts.token_program,
+                    accounts.life_vault.to_account_info(),
+                    accounts.user_life_account.to_account_info(),
+                    event,
+                    user_bet.life_amount,
+                )?;
+                event.doom_pool.saturating_add(event.life_pool)
+            }
+            EventKind::Categorical => {
+                let event_outcomes = accounts
+                    .event_outcomes
+                    .as_ref()
+                    .ok_or(PredictionError::MissingEventOutcomes)?;
+                let outcome_vaults = event_outcomes.outcome_vaults(&event.key(), ctx.remaining_accounts)?;
+                for (vault, stake) in outcome_vaults.iter().zip(&user_bet.outcome_amounts) {
+                    transfer_from_vault(
+                        &accounts.token_program,
+                        vault.clone(),
+                        accounts.user_doom_account.to_account_info(),
+                        event,
+                        *stake,
+                    )?;
+                }
+                event_outcomes.total_pool()
+            }
+        };
+
+        // Exit penalties are shared out between the bettors who stayed in
+        for (penalty_pool, vault, to) in [
+            (
+                event.doom_penalty_pool,
+                accounts.doom_vault.to_account_info(),
+                accounts.user_doom_account.to_account_info(),
+            ),
+            (
+                event.life_penalty_pool,
+                accounts.life_vault.to_account_info(),
+                accounts.user_life_account.to_account_info(),
+            ),
+        ] {
+            if penalty_pool > 0 {
+                transfer_from_vault(
+                    &accounts.token_program,
+                    vault,
+                    to,
+                    event,
+                    pro_rata(amount, penalty_pool, total_pool)?,
+                )?;
+            }
+        }
+
+        let event = &mut ctx.accounts.event;
+        event.settled_stake = event.settled_stake.checked_add(amount).ok_or(PredictionError::Overflow)?;
+        if event.settled_stake == total_pool {
+            event.status = event.status.transition(EventStatus::Settled)?;
+        }
+        let event_id = event.event_id;
+
+        let user_bet = &mut ctx.accounts.user_bet;
+        user_bet.refunded = true;
+
+        msg!("Refunded {} on event {}", amount, event_id);
+        Ok(())
+    }
+
+    /// Redeem outcome shares of a tokenized event by burning them
+    ///
+    /// Winning shares pay back their stake plus a pro-rata share of the losing
+    /// pool, less fees; on a draw every share pays back its stake less fees.
+    /// Shares of a cancelled, expired or invalid event are refunded in full.
+    pub fn redeem_shares(ctx: Context<RedeemShares>, outcome: Outcome, amount: u64) -> Result<()> {
+        require!(amount > 0, PredictionError::InvalidBetAmount);
+        let event = &ctx.accounts.event;
+        require_keys_eq!(
+            ctx.accounts.share_mint.key(),
+            event.share_mint_address(&event.key(), outcome)?,
+            PredictionError::InvalidShareMint
+        );
+
+        let payout = pay_redemption(ctx.accounts, outcome, amount)?;
+
+        let burn = Burn {
+            mint: ctx.accounts.share_mint.to_account_info(),
+            from: ctx.accounts.holder_share_account.to_account_info(),
+            authority: ctx.accounts.holder.to_account_info(),
+        };
+        token::burn(
+            CpiContext::new(ctx.accounts.token_program.to_account_info(), burn),
+            amount,
+        )?;
+
+        let event = &mut ctx.accounts.event;
+        event.settled_stake = event
+            .settled_stake
+            .checked_add(payout.stake)
+            .ok_or(PredictionError::Overflow)?;
+        if event.settled_stake == payout.winning_pool {
+            event.status = event.status.transition(EventStatus::Settled)?;
+        }
+        event.creator_doom_fees = event
+            .creator_doom_fees
+            .checked_add(payout.creator_doom_fee)
+            .ok_or(PredictionError::Overflow)?;
+        event.creator_life_fees = event
+            .creator_life_fees
+            .checked_add(payout.creator_life_fee)
+            .ok_or(PredictionError::Overflow)?;
+        let event_id = event.event_id;
+
+        let platform_config = &mut ctx.accounts.platform_config;
+        platform_config.total_doom_fees = platform_config.total_doom_fees.saturating_add(payout.doom_fee);
+        platform_config.total_life_fees = platform_config.total_life_fees.saturating_add(payout.life_fee);
+
+        msg!(
+            "Redeemed {} {:?} shares for {} stake + {} winnings on event {}",
+            amount,
+            outcome,
+            payout.returned,
+            payout.winnings,
+            event_id
+        );
+        Ok(())
+    }
+
+    /// Close a claimed or refunded bet, returning its rent to the bettor
+    ///
+    /// Bets on tokenized events are settled through their shares, so those
+    /// can be closed once the event has settled. Bets left open when the event
+    /// was swept can always be closed, and closing the last one closes the
+    /// swept event.
+    pub fn close_user_bet(ctx: Context<CloseUserBet>) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        let user_bet = &ctx.accounts.user_bet;
+        // Shares change hands, so a tokenized bet is only done with once every
+        // share that pays out has been redeemed
+        require!(
+            user_bet.claimed
+                || user_bet.refunded
+                || user_bet.settled_loss
+                || (event.tokenized && event.status == EventStatus::Settled)
+                || event.status == EventStatus::Swept,
+            PredictionError::BetNotSettled
+        );
+
+        event.outstanding_bets = event.outstanding_bets.saturating_sub(1);
+        let event_id = event.event_id;
+        close_swept_event(&ctx.accounts.event, &ctx.accounts.creator)?;
+
+        msg!("Closed bet of {} on event {}", ctx.accounts.user.key(), event_id);
+        Ok(())
+    }
+
+    /// Close a finished event and its vaults, returning their rent to the creator
+    ///
+    /// Permissionless. Needs every bet and oracle vote closed and the
+    /// creator's fees claimed, unless the event's sweep window has passed since
+    /// the resolution deadline; anything left in the vaults is then swept to
+    /// the treasury. An event swept with bets or votes still open stays behind
+    /// as Swept, so its address can't be reused, until the last of them is
+    /// closed. The creation bond must have been returned. Categorical events
+    /// pass every outcome vault as remaining accounts.
+    pub fn close_event<'info>(ctx: Context<'_, '_, '_, 'info, CloseEvent<'info>>) -> Result<()> {
+        let clock = Clock::get()?;
+        let event = &ctx.accounts.event;
+
+        require!(
+            matches!(
+                event.status,
+                EventStatus::Resolved
+                    | EventStatus::Cancelled
+                    | EventStatus::Expired
+                    | EventStatus::Settled
+            ),
+            PredictionError::EventNotFinalized
+        );
+        require!(event.creation_bond == 0, PredictionError::CreationBondNotReturned);
+        let settled = event.accounts_closed()
+            && event.creator_doom_fees == 0
+            && event.creator_life_fees == 0
+            && (!event.tokenized || event.status == EventStatus::Settled);
+        let sweep_at = event
+            .resolution_deadline
+            .checked_add(event.sweep_window)
+            .ok_or(PredictionError::Overflow)?;
+        require!(
+            settled || clock.unix_timestamp >= sweep_at,
+            PredictionError::EventNotSettled
+        );
+
+        let accounts = &ctx.accounts;
+        let mut doom_swept = sweep_vault(
+            &accounts.token_program,
+            accounts.doom_vault.to_account_info(),
+            accounts.doom_treasury.to_account_info(),
+            accounts.creator.to_account_info(),
+            event,
+        )?;
+        let life_swept = sweep_vault(
+            &accounts.token_program,
+            accounts.life_vault.to_account_info(),
+            accounts.life_treasury.to_account_info(),
+            accounts.creator.to_account_info(),
+            event,
+        )?;
+        if event.kind == EventKind::Categorical {
+            let event_outcomes = accounts
+                .event_outcomes
+                .as_ref()
+                .ok_or(PredictionError::MissingEventOutcomes)?;
+            let outcome_vaults = event_outcomes.outcome_vaults(&event.key(), ctx.remaining_accounts)?;
+            for (vault, &bump) in outcome_vaults.iter().zip(&event_outcomes.vault_bumps) {
+                // Outcomes nobody bet on never got a vault
+                if bump == 0 {
+                    continue;
+                }
+                let swept = sweep_vault(
+                    &accounts.token_program,
+                    vault.clone(),
+                    accounts.doom_treasury.to_account_info(),
+                    accounts.creator.to_account_info(),
+                    event,
+                )?;
+                doom_swept = doom_swept.checked_add(swept).ok_or(PredictionError::Overflow)?;
+            }
+        }
+        let event_id = event.event_id;
+
+        let platform_config = &mut ctx.accounts.platform_config;
+        platform_config.total_doom_fees = platform_config.total_doom_fees.saturating_add(doom_swept);
+        platform_config.total_life_fees = platform_config.total_life_fees.saturating_add(life_swept);
+
+        msg!(
+            "Event {} closed, swept {} DOOM / {} LIFE to the treasury",
+            event_id,
+            doom_swept,
+            life_swept
+        );
+
+        if ctx.accounts.event.accounts_closed() {
+            ctx.accounts.event.close(ctx.accounts.creator.to_account_info())?;
+        } else {
+            let event = &mut ctx.accounts.event;
+            event.status = event.status.transition(EventStatus::Swept)?;
+        }
+        Ok(())
+    }
+
+    /// Withdraw collected fees from the DOOM or LIFE treasury
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        let treasury = &ctx.accounts.treasury;
+        require!(amount > 0, PredictionError::InvalidWithdrawAmount);
+        require!(
+            amount <= treasury.amount,
+            PredictionError::InsufficientTreasuryBalance
+        );
+
+        transfer_from_platform_vault(
+            &ctx.accounts.token_program,
+            treasury,
+            ctx.accounts.destination.to_account_info(),
+            &ctx.accounts.platform_config,
+            amount,
+        )?;
+
+        emit!(FeesWithdrawn {
+            mint: ctx.accounts.mint.key(),
+            destination: ctx.accounts.destination.key(),
+            authority: ctx.accounts.authority.key(),
+            amount,
+            remaining: treasury.amount - amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Withdrew {} fees to {}", amount, ctx.accounts.destination.key());
+        Ok(())
+    }
+
+    /// Set an event's bet limits, replacing the platform defaults
+    ///
+    /// Only the creator, and only before the first bet.
+    pub fn set_event_bet_limits(ctx: Context<SetEventBetLimits>, bet_limits: BetLimits) -> Result<()> {
+        bet_limits.validate()?;
+
+        let event = &mut ctx.accounts.event;
+        require!(event.total_bettors == 0, PredictionError::BetLimitsLocked);
+        event.bet_limits = bet_limits;
+
+        msg!("Bet limits updated for event {}", event.event_id);
+        Ok(())
+    }
+
+    /// Move an event's betting cutoff, anywhere up to its deadline
+    ///
+    /// Only the creator, and only while betting is still open. Once bets are
+    /// placed the cutoff can only be brought forward.
+    pub fn set_betting_closes_at(ctx: Context<SetBettingClosesAt>, betting_closes_at: i64) -> Result<()> {
+        let clock = Clock::get()?;
+        let event = &mut ctx.accounts.event;
+
+        require!(
+            event.status == EventStatus::Active && clock.unix_timestamp < event.betting_closes_at,
+            PredictionError::BettingClosed
+        );
+        require!(
+            betting_closes_at > clock.unix_timestamp && betting_closes_at <= event.deadline,
+            PredictionError::InvalidBettingCutoff
+        );
+        require!(
+            event.total_bettors == 0 || betting_closes_at <= event.betting_closes_at,
+            PredictionError::BettingCutoffLocked
+        );
+        event.betting_closes_at = betting_closes_at;
+
+        msg!("Betting on event {} closes at {}", event.event_id, betting_closes_at);
+        Ok(())
+    }
+
+    /// Pause or unpause a single event's betting, resolution and claims
+    pub fn set_event_paused(ctx: Context<SetEventPaused>, paused: bool) -> Result<()> {
+        let event = &mut ctx.accounts.event;
+        event.paused = paused;
+
+        msg!("Event {} {}", event.event_id, if paused { "paused" } else { "unpaused" });
+        Ok(())
+    }
+
+    /// Update platform configuration
+    ///
+    /// The fee needs the fee manager, pausing the pauser, and everything else
+    /// the authority. Fee and oracle changes, and shortening the config
+    /// delay, are queued for `apply_pending_config` once the delay has passed.
+    /// `paused` replaces the whole set of `PAUSE_*` flags.
+    pub fn update_platform(
+        ctx: Context<UpdatePlatform>,
+        fee_basis_points: Option<u16>,
+        new_oracle: Option<Pubkey>,
+        paused: Option<u8>,
+        dispute_window: Option<i64>,
+        dispute_bond: Option<u64>,
+        config_delay: Option<i64>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let signer = ctx.accounts.authority.key();
+        let config = &mut ctx.accounts.platform_config;
+        let mut queued = false;
+
+        // Lengthening the delay only makes changes safer, so it applies at once
+        if let Some(delay) = config_delay {
+            require_keys_eq!(signer, config.authority, PredictionError::Unauthorized);
+            require!(delay >= 0, PredictionError::InvalidConfigDelay);
+            if delay >= config.config_delay {
+                config.config_delay = delay;
+            } else {
+                config.pending_config_delay = Some(delay);
+                queued = true;
+            }
+        }
+        if let Some(fee) = fee_basis_points {
+            require_keys_eq!(signer, config.fee_manager, PredictionError::Unauthorized);
+            require!(fee <= 10000, PredictionError::InvalidFeeBasisPoints);
+            config.pending_fee_basis_points = Some(fee);
+            queued = true;
+        }
+        if let Some(oracle) = new_oracle {
+            require_keys_eq!(signer, config.authority, PredictionError::Unauthorized);
+            config.pending_oracle = Some(oracle);
+            queued = true;
+        }
+        if let Some(flags) = paused {
+            require_keys_eq!(signer, config.pauser, PredictionError::Unauthorized);
+            require!(flags & !PAUSE_ALL == 0, PredictionError::InvalidPauseFlags);
+            config.paused = flags;
+        }
+        if let Some(window) = dispute_window {
+            require_keys_eq!(signer, config.authority, PredictionError::Unauthorized);
+            require!(window >= 0, PredictionError::InvalidDisputeWindow);
+            config.dispute_window = window;
+        }
+        if let Some(bond) = dispute_bond {
+            require_keys_eq!(signer, config.authority, PredictionError::Unauthorized);
+            config.dispute_bond = bond;
+        }
+
+        if queued {
+            config.pending_config_effective_at = clock
+                .unix_timestamp
+                .checked_add(config.config_delay)
+                .ok_or(PredictionError::Overflow)?;
+            msg!("Config change queued until {}", config.pending_config_effective_at);
+        }
+
+        Ok(())
+    }
+
+    /// Apply queued fee, oracle, config delay and creator fee share changes
+    /// once they take effect
+    ///
+    /// Permissionless.
+    pub fn apply_pending_config(ctx: Context<ApplyPendingConfig>) -> Result<()> {
+        let clock = Clock::get()?;
+        let config = &mut ctx.accounts.platform_config;
+
+        require!(
+            config.pending_fee_basis_points.is_some()
+                || config.pending_oracle.is_some()
+                || config.pending_config_delay.is_some()
+                || config.pending_creator_fee_bps.is_some(),
+            PredictionError::NoPendingConfig
+        );
+        require!(
+            clock.unix_timestamp >= config.pending_config_effective_at,
+            PredictionError::PendingConfigNotReady
+        );
+
+        if let Some(fee) = config.pending_fee_basis_points.take() {
+            config.fee_basis_points = fee;
+        }
+        if let Some(oracle) = config.pending_oracle.take() {
+            config.oracle = oracle;
+        }
+        if let Some(delay) = config.pending_config_delay.take() {
+            config.config_delay = delay;
+        }
+        if let Some(share) = config.pending_creator_fee_bps.take() {
+            config.creator_fee_bps = share;
+        }
+
+        msg!("Pending config applied");
+        Ok(())
+    }
+
+    /// Propose a new platform authority, who must accept before taking over
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        let config = &mut ctx.accounts.platform_config;
+        config.pending_authority = Some(new_authority);
+
+        msg!("Authority transfer to {} proposed", new_authority);
+        Ok(())
+    }
+
+    /// Accept a proposed authority transfer
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let config = &mut ctx.accounts.platform_config;
+        config.authority = ctx.accounts.new_authority.key();
+        config.pending_authority = None;
+
+        msg!("Authority transferred to {}", config.authority);
+        Ok(())
+    }
+
+    /// Reassign the pauser, fee manager and event moderator roles
+    pub fn set_roles(
+        ctx: Context<SetRoles>,
+        pauser: Option<Pubkey>,
+        fee_manager: Option<Pubkey>,
+        moderator: Option<Pubkey>,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.platform_config;
+
+        if let Some(pauser) = pauser {
+            config.pauser = pauser;
+        }
+        if let Some(fee_manager) = fee_manager {
+            config.fee_manager = fee_manager;
+        }
+        if let Some(moderator) = moderator {
+            config.moderator = moderator;
+        }
+
+        Ok(())
+    }
+
+    /// Update the event creation bond and the creators' share of the platform fee
+    ///
+    /// The creator fee share only applies to events created afterwards and is
+    /// queued behind `config_delay` like fee changes.
+    pub fn update_creator_settings(
+        ctx: Context<UpdatePlatform>,
+        creation_bond: Option<u64>,
+        creator_fee_bps: Option<u16>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let config = &mut ctx.accounts.platform_config;
+        require_keys_eq!(
+            ctx.accounts.authority.key(),
+            config.fee_manager,
+            PredictionError::Unauthorized
+        );
+
+        if let Some(bond) = creation_bond {
+            config.creation_bond = bond;
+        }
+        if let Some(share) = creator_fee_bps {
+            require!(share <= 10000, PredictionError::InvalidFeeBasisPoints);
+            config.pending_creator_fee_bps = Some(share);
+            config.pending_config_effective_at = clock
+                .unix_timestamp
+                .checked_add(config.config_delay)
+                .ok_or(PredictionError::Overflow)?;
+            msg!("Config change queued until {}", config.pending_config_effective_at);
+        }
+
+        Ok(())
+    }
+
+    /// Update the default bet limits copied into new events
+    pub fn update_bet_limits(ctx: Context<UpdatePlatform>, bet_limits: BetLimits) -> Result<()> {
+        let config = &mut ctx.accounts.platform_config;
+        require_keys_eq!(
+            ctx.accounts.authority.key(),
+            config.authority,
+            PredictionError::Unauthorized
+        );
+        bet_limits.validate()?;
+
+        config.bet_limits = bet_limits;
+        Ok(())
+    }
+
+    /// Update the betting cutoff, early-exit penalty and sweep window applied to new events
+    pub fn update_betting_rules(
+        ctx: Context<UpdatePlatform>,
+        betting_cutoff: Option<i64>,
+        exit_penalty_bps: Option<u16>,
+        sweep_window: Option<i64>,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.platform_config;
+        require_keys_eq!(
+            ctx.accounts.authority.key(),
+            config.authority,
+            PredictionError::Unauthorized
+        );
+
+        if let Some(cutoff) = betting_cutoff {
+            require!(cutoff >= 0, PredictionError::InvalidBettingCutoff);
+            config.betting_cutoff = cutoff;
+        }
+        if let Some(penalty) = exit_penalty_bps {
+            require!(penalty <= 10000, PredictionError::InvalidFeeBasisPoints);
+            config.exit_penalty_bps = penalty;
+        }
+        if let Some(window) = sweep_window {
+            require!(window >= 0, PredictionError::InvalidSweepWindow);
+            config.sweep_window = window;
+        }
+
+        Ok(())
+    }
+}
+
+// Helpers
+
+/// Tokens paid out on a winning claim
+pub struct Payout {
+    /// Stake counted towards settling the event
+    pub stake: u64,
+    /// Stake paid back in the token it was placed in
+    pub returned: u64,
+    /// Share of the opposing pools, net of fees
+    pub winnings: u64,
+    /// Platform's part of the fee
+    pub doom_fee: u64,
+    pub life_fee: u64,
+    /// Creator's part of the fee, left in the event's DOOM and LIFE vaults
+    pub creator_doom_fee: u64,
+    pub creator_life_fee: u64,
+    /// Total stake that can be claimed on the event
+    pub winning_pool: u64,
+}
+
+/// amount * numerator / denominator, computed in u128
+fn pro_rata(amount: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    let result = (amount as u128)
+        .checked_mul(numerator as u128)
+        .ok_or(PredictionError::Overflow)?
+        .checked_div(denominator as u128)
+        .ok_or(PredictionError::Overflow)?;
+    u64::try_from(result).map_err(|_| error!(PredictionError::Overflow))
+}
+
+/// e^-x, with x and the result in `LMSR_SCALE` fixed point
+///
+/// Reduces x to k * ln 2 + r and sums the Taylor series of e^-r.
+fn exp_neg(x: u128) -> u128 {
+    let halvings = x / LMSR_LN_2;
+    if halvings >= 64 {
+        return 0;
+    }
+    let r = x % LMSR_LN_2;
+
+    let mut sum = LMSR_SCALE as i128;
+    let mut term = LMSR_SCALE;
+    for n in 1..=30u128 {
+        term = term * r / LMSR_SCALE / n;
+        if term == 0 {
+            break;
+        }
+        if n % 2 == 1 {
+            sum -= term as i128;
+        } else {
+            sum += term as i128;
+        }
+    }
+    (sum as u128) >> halvings
+}
+
+/// ln(1 + y) for y in [0, 1], in `LMSR_SCALE` fixed point
+///
+/// Sums the series of 2 * atanh(y / (2 + y)).
+fn ln_1p(y: u128) -> u128 {
+    let z = y * LMSR_SCALE / (2 * LMSR_SCALE + y);
+    let z_squared = z * z / LMSR_SCALE;
+
+    let mut sum = 0;
+    let mut power = z;
+    let mut n = 1;
+    while power > 0 {
+        sum += power / n;
+        power = power * z_squared / LMSR_SCALE;
+        n += 2;
+    }
+    2 * sum
+}
+
+/// LMSR cost function b * ln(e^(q_doom / b) + e^(q_life / b)), rounded up
+///
+/// Evaluated as max(q) + b * ln(1 + e^(-|q_doom - q_life| / b)) to stay in range.
+fn lmsr_cost(doom_shares: u64, life_shares: u64, liquidity: u64) -> Result<u64> {
+    let spread = (doom_shares.abs_diff(life_shares) as u128)
+        .checked_mul(LMSR_SCALE)
+        .ok_or(PredictionError::Overflow)?
+        / liquidity as u128;
+    let premium = (liquidity as u128)
+        .checked_mul(ln_1p(exp_neg(spread)))
+        .ok_or(PredictionError::Overflow)?;
+    let cost = (doom_shares.max(life_shares) as u128)
+        .checked_add(premium.div_ceil(LMSR_SCALE))
+        .ok_or(PredictionError::Overflow)?;
+    u64::try_from(cost).map_err(|_| error!(PredictionError::Overflow))
+}
+
+/// DOOM charged to move the LMSR market from `before` to `after` shares
+///
+/// Rounded up by a unit so the market maker stays solvent despite rounding.
+fn lmsr_price(before: (u64, u64), after: (u64, u64), liquidity: u64) -> Result<u64> {
+    let cost_before = lmsr_cost(before.0, before.1, liquidity)?;
+    let cost_after = lmsr_cost(after.0, after.1, liquidity)?;
+    cost_after
+        .saturating_sub(cost_before)
+        .checked_add(1)
+        .ok_or_else(|| error!(PredictionError::Overflow))
+}
+
+/// Platform fee on an amount
+fn fee_for(amount: u64, fee_basis_points: u16) -> Result<u64> {
+    pro_rata(amount, fee_basis_points as u64, 10000)
+}
+
+/// Split a payout into (winnings, platform fee, creator fee)
+fn split_fee(share: u64, fee_basis_points: u16, creator_fee_bps: u16) -> Result<(u64, u64, u64)> {
+    let fee = fee_for(share, fee_basis_points)?;
+    let winnings = share.checked_sub(fee).ok_or(PredictionError::Overflow)?;
+    let creator_fee = fee_for(fee, creator_fee_bps)?;
+    Ok((winnings, fee - creator_fee, creator_fee))
+}
+
+/// Pay a share of an opposing pool: winnings to the user, fee to the treasury
+///
+/// The creator's cut of the fee is moved to `creator_vault`, the event's own
+/// vault for that token, until claimed with `claim_creator_fees`.
+/// Returns (winnings, platform fee, creator fee).
+fn pay_share<'info>(
+    accounts: &ClaimWinnings<'info>,
+    vault: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    fee_to: AccountInfo<'info>,
+    creator_vault: AccountInfo<'info>,
+    share: u64,
+    fee_basis_points: u16,
+) -> Result<(u64, u64, u64)> {
+    let (winnings, platform_fee, creator_fee) =
+        split_fee(share, fee_basis_points, accounts.event.creator_fee_bps)?;
+    transfer_from_vault(&accounts.token_program, vault.clone(), to, &accounts.event, winnings)?;
+    transfer_from_vault(&accounts.token_program, vault.clone(), fee_to, &accounts.event, platform_fee)?;
+    if vault.key() != creator_vault.key() {
+        transfer_from_vault(&accounts.token_program, vault, creator_vault, &accounts.event, creator_fee)?;
+    }
+    Ok((winnings, platform_fee, creator_fee))
+}
+
+/// Pay a winning binary position: stake back in the winning token, share of
+/// the losing pool (less fee) in the losing token
+fn pay_binary_winnings(
+    accounts: &ClaimWinnings,
+    winning_outcome: Outcome,
+    fee_basis_points: u16,
+) -> Result<Payout> {
+    let event = &accounts.event;
+    let stake = accounts.user_bet.stake_on(winning_outcome);
+    let (winning_pool, losing_pool) = match winning_outcome {
+        Outcome::Doom => (event.doom_pool, event.life_pool),
+        _ => (event.life_pool, event.doom_pool),
+    };
+
+    // share = stake / winning_pool * losing_pool
+    let share = pro_rata(stake, losing_pool, winning_pool)?;
+
+    let (stake_vault, stake_to, share_vault, share_to, fee_to) = match winning_outcome {
+        Outcome::Doom => (
+            accounts.doom_vault.to_account_info(),
+            accounts.user_doom_account.to_account_info(),
+            accounts.life_vault.to_account_info(),
+            accounts.user_life_account.to_account_info(),
+            accounts.life_treasury.to_account_info(),
+        ),
+        _ => (
+            accounts.life_vault.to_account_info(),
+            accounts.user_life_account.to_account_info(),
+            accounts.doom_vault.to_account_info(),
+            accounts.user_doom_account.to_account_info(),
+            accounts.doom_treasury.to_account_info(),
+        ),
+    };
+    transfer_from_vault(&accounts.token_program, stake_vault, stake_to, event, stake)?;
+    let (winnings, fee, creator_fee) = pay_share(
+        accounts,
+        share_vault.clone(),
+        share_to,
+        fee_to,
+        share_vault,
+        share,
+        fee_basis_points,
+    )?;
+
+    let (doom_fee, life_fee, creator_doom_fee, creator_life_fee) = match winning_outcome {
+        Outcome::Doom => (0, fee, 0, creator_fee),
+        _ => (fee, 0, creator_fee, 0),
+    };
+    Ok(Payout {
+        stake,
+        returned: stake,
+        winnings,
+        doom_fee,
+        life_fee,
+        creator_doom_fee,
+        creator_life_fee,
+        winning_pool,
+    })
+}
+
+/// Pay a winning LMSR position: 1 DOOM per winning share, or half per share
+/// of either side on a draw, with the fee taken only on what exceeds the
+/// position's cost
+fn pay_lmsr_winnings<'info>(
+    accounts: &ClaimWinnings<'info>,
+    winning_outcome: Outcome,
+    fee_basis_points: u16,
+) -> Result<Payout> {
+    let event = &accounts.event;
+    let user_bet = &accounts.user_bet;
+    let (stake, owed, winning_pool) = match winning_outcome {
+        Outcome::Draw => {
+            let stake = user_bet.total_stake();
+            (stake, stake / 2, event.doom_pool.saturating_add(event.life_pool))
+        }
+        Outcome::Doom => (user_bet.doom_amount, user_bet.doom_amount, event.doom_pool),
+        _ => (user_bet.life_amount, user_bet.life_amount, event.life_pool),
+    };
+
+    let returned = owed.min(user_bet.lmsr_cost);
+    transfer_from_vault(
+        &accounts.token_program,
+        accounts.doom_vault.to_account_info(),
+        accounts.user_doom_account.to_account_info(),
+        event,
+        returned,
+    )?;
+    let (winnings, fee, creator_fee) = pay_share(
+        accounts,
+        accounts.doom_vault.to_account_info(),
+        accounts.user_doom_account.to_account_info(),
+        accounts.doom_treasury.to_account_info(),
+        accounts.doom_vault.to_account_info(),
+        owed - returned,
+        fee_basis_points,
+    )?;
+
+    Ok(Payout {
+        stake,
+        returned,
+        winnings,
+        doom_fee: fee,
+        life_fee: 0,
+        creator_doom_fee: creator_fee,
+        creator_life_fee: 0,
+        winning_pool,
+    })
+}
+
+/// Pay a winning categorical position: stake back from the winning outcome
+/// vault and a pro-rata share (less fee) of every losing outcome vault
+fn pay_categorical_winnings<'info>(
+    accounts: &ClaimWinnings<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    winning_index: u8,
+    fee_basis_points: u16,
+) -> Result<Payout> {
+    let event = &accounts.event;
+    let stake = accounts.user_bet.stake_on(Outcome::Categorical(winning_index));
+    let event_outcomes = accounts
+        .event_outcomes
+        .as_ref()
+        .ok_or(PredictionError::MissingEventOutcomes)?;
+    let outcome_vaults = event_outcomes.outcome_vaults(&event.key(), remaining_accounts)?;
+    let winning_pool = event_outcomes.pools[winning_index as usize];
+
+    let mut winnings: u64 = 0;
+    let mut fee: u64 = 0;
+    let mut creator_fee: u64 = 0;
+    for (index, (vault, &pool)) in outcome_vaults.iter().zip(&event_outcomes.pools).enumerate() {
+        if index == winning_index as usize {
+            transfer_from_vault(
+                &accounts.token_program,
+                vault.clone(),
+                accounts.user_doom_account.to_account_info(),
+                event,
+                stake,
+            )?;
+            continue;
+        }
+
+        let (share_winnings, share_fee, share_creator_fee) = pay_share(
+            accounts,
+            vault.clone(),
+            accounts.user_doom_account.to_account_info(),
+            accounts.doom_treasury.to_account_info(),
+            accounts.doom_vault.to_account_info(),
+            pro_rata(stake, pool, winning_pool)?,
+            fee_basis_points,
+        )?;
+        winnings = winnings.checked_add(share_winnings).ok_or(PredictionError::Overflow)?;
+        fee = fee.checked_add(share_fee).ok_or(PredictionError::Overflow)?;
+        creator_fee = creator_fee.checked_add(share_creator_fee).ok_or(PredictionError::Overflow)?;
+    }
+
+    Ok(Payout {
+        stake,
+        returned: stake,
+        winnings,
+        doom_fee: fee,
+        life_fee: 0,
+        creator_doom_fee: creator_fee,
+        creator_life_fee: 0,
+        winning_pool,
+    })
+}
+
+/// Pay both sides of a scalar position
+///
+/// Longs (Doom) are entitled to the fraction of each pool given by where the
+/// value falls in the range, shorts (Life) to the rest. Each side gets that
+/// fraction of its own stake back and a pro-rata share (less fee) of the
+/// opposing side's entitlement to the other pool.
+fn pay_scalar_winnings(
+    accounts: &ClaimWinnings,
+    value: i64,
+    fee_basis_points: u16,
+) -> Result<Payout> {
+    let event = &accounts.event;
+    let user_bet = &accounts.user_bet;
+    let (long_weight, short_weight, range) = event.scalar_weights(value);
+
+    // Long side: DOOM stake back, share of the LIFE pool
+    let long_returned = pro_rata(user_bet.doom_amount, long_weight, range)?;
+    let long_share = if event.doom_pool == 0 {
+        0
+    } else {
+        pro_rata(
+            user_bet.doom_amount,
+            pro_rata(event.life_pool, long_weight, range)?,
+            event.doom_pool,
+        )?
+    };
+    transfer_from_vault(
+        &accounts.token_program,
+        accounts.doom_vault.to_account_info(),
+        accounts.user_doom_account.to_account_info(),
+        event,
+        long_returned,
+    )?;
+    let (long_winnings, life_fee, creator_life_fee) = pay_share(
+        accounts,
+        accounts.life_vault.to_account_info(),
+        accounts.user_life_account.to_account_info(),
+        accounts.life_treasury.to_account_info(),
+        accounts.life_vault.to_account_info(),
+        long_share,
+        fee_basis_points,
+    )?;
+
+    // Short side: LIFE stake back, share of the DOOM pool
+    let short_returned = pro_rata(user_bet.life_amount, short_weight, range)?;
+    let short_share = if event.life_pool == 0 {
+        0
+    } else {
+        pro_rata(
+            user_bet.life_amount,
+            pro_rata(event.doom_pool, short_weight, range)?,
+            event.life_pool,
+        )?
+    };
+    transfer_from_vault(
+        &accounts.token_program,
+        accounts.life_vault.to_account_info(),
+        accounts.user_life_account.to_account_info(),
+        event,
+        short_returned,
+    )?;
+    let (short_winnings, doom_fee, creator_doom_fee) = pay_share(
+        accounts,
+        accounts.doom_vault.to_account_info(),
+        accounts.user_doom_account.to_account_info(),
+        accounts.doom_treasury.to_account_info(),
+        accounts.doom_vault.to_account_info(),
+        short_share,
+        fee_basis_points,
+    )?;
+
+    let long_pool = if long_weight > 0 { event.doom_pool } else { 0 };
+    let short_pool = if short_weight > 0 { event.life_pool } else { 0 };
+    Ok(Payout {
+        stake: event.claimable_stake(user_bet),
+        returned: long_returned.saturating_add(short_returned),
+        winnings: long_winnings.saturating_add(short_winnings),
+        doom_fee,
+        life_fee,
+        creator_doom_fee,
+        creator_life_fee,
+        winning_pool: long_pool.saturating_add(short_pool),
+    })
+}
+
+/// Return every position of a bet on a drawn event, less the platform fee
+fn pay_draw<'info>(
+    accounts: &ClaimWinnings<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    fee_basis_points: u16,
+) -> Result<Payout> {
+    let event = &accounts.event;
+    let user_bet = &accounts.user_bet;
+
+    let mut returned: u64 = 0;
+    let mut doom_fee: u64 = 0;
+    let mut life_fee: u64 = 0;
+    let mut creator_doom_fee: u64 = 0;
+    let mut creator_life_fee: u64 = 0;
+    let total_pool = match event.kind {
+        EventKind::Binary | EventKind::Scalar => {
+            let (doom_returned, fee, creator_fee) = pay_share(
+                accounts,
+                accounts.doom_vault.to_account_info(),
+                accounts.user_doom_account.to_account_info(),
+                accounts.doom_treasury.to_account_info(),
+                accounts.doom_vault.to_account_info(),
+                user_bet.doom_amount,
+                fee_basis_points,
+            )?;
+            doom_fee = fee;
+            creator_doom_fee = creator_fee;
+            let (life_returned, fee, creator_fee) = pay_share(
+                accounts,
+                accounts.life_vault.to_account_info(),
+                accounts.user_life_account.to_account_info(),
+                accounts.life_treasury.to_account_info(),
+                accounts.life_vault.to_account_info(),
+                user_bet.life_amount,
+                fee_basis_points,
+            )?;
+            life_fee = fee;
+            creator_life_fee = creator_fee;
+            returned = doom_returned.saturating_add(life_returned);
+            event.doom_pool.saturating_add(event.life_pool)
+        }
+        EventKind::Categorical => {
+            let event_outcomes = accounts
+                .event_outcomes
+                .as_ref()
+                .ok_or(PredictionError::MissingEventOutcomes)?;
+            let outcome_vaults = event_outcomes.outcome_vaults(&event.key(), remaining_accounts)?;
+            for (vault, stake) in outcome_vaults.iter().zip(&user_bet.outcome_amounts) {
+                let (stake_returned, fee, creator_fee) = pay_share(
+                    accounts,
+                    vault.clone(),
+                    accounts.user_doom_account.to_account_info(),
+                    accounts.doom_treasury.to_account_info(),
+                    accounts.doom_vault.to_account_info(),
+                    *stake,
+                    fee_basis_points,
+                )?;
+                returned = returned.checked_add(stake_returned).ok_or(PredictionError::Overflow)?;
+                doom_fee = doom_fee.checked_add(fee).ok_or(PredictionError::Overflow)?;
+                creator_doom_fee = creator_doom_fee.checked_add(creator_fee).ok_or(PredictionError::Overflow)?;
+            }
+            event_outcomes.total_pool()
+        }
+    };
+
+    Ok(Payout {
+        stake: user_bet.total_stake(),
+        returned,
+        winnings: 0,
+        doom_fee,
+        life_fee,
+        creator_doom_fee,
+        creator_life_fee,
+        winning_pool: total_pool,
+    })
+}
+
+/// Pay a claim its pro-rata share (less fee) of the exit penalties, by the
+/// stake it settles out of the winning pool
+fn pay_penalty_shares(
+    accounts: &ClaimWinnings,
+    payout: &mut Payout,
+    fee_basis_points: u16,
+) -> Result<()> {
+    let event = &accounts.event;
+    if event.doom_penalty_pool > 0 {
+        let (winnings, fee, creator_fee) = pay_share(
+            accounts,
+            accounts.doom_vault.to_account_info(),
+            accounts.user_doom_account.to_account_info(),
+            accounts.doom_treasury.to_account_info(),
+            accounts.doom_vault.to_account_info(),
+            pro_rata(payout.stake, event.doom_penalty_pool, payout.winning_pool)?,
+            fee_basis_points,
+        )?;
+        payout.winnings = payout.winnings.checked_add(winnings).ok_or(PredictionError::Overflow)?;
+        payout.doom_fee = payout.doom_fee.checked_add(fee).ok_or(PredictionError::Overflow)?;
+        payout.creator_doom_fee =
+            payout.creator_doom_fee.checked_add(creator_fee).ok_or(PredictionError::Overflow)?;
+    }
+    if event.life_penalty_pool > 0 {
+        let (winnings, fee, creator_fee) = pay_share(
+            accounts,
+            accounts.life_vault.to_account_info(),
+            accounts.user_life_account.to_account_info(),
+            accounts.life_treasury.to_account_info(),
+            accounts.life_vault.to_account_info(),
+            pro_rata(payout.stake, event.life_penalty_pool, payout.winning_pool)?,
+            fee_basis_points,
+        )?;
+        payout.winnings = payout.winnings.checked_add(winnings).ok_or(PredictionError::Overflow)?;
+        payout.life_fee = payout.life_fee.checked_add(fee).ok_or(PredictionError::Overflow)?;
+        payout.creator_life_fee =
+            payout.creator_life_fee.checked_add(creator_fee).ok_or(PredictionError::Overflow)?;
+    }
+    Ok(())
+}
+
+/// Transfer tokens out of an event vault, signing as the event PDA
+fn transfer_from_vault<'info>(
+    token_program: &Program<'info, Token>,
+    vault: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    event: &Account<'info, PredictionEvent>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let event_id = event.event_id.to_le_bytes();
+    let seeds = &[b"event".as_ref(), event_id.as_ref(), &[event.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let transfer = Transfer {
+        from: vault,
+        to,
+        authority: event.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), transfer, signer_seeds),
+        amount,
+    )
+}
+
+/// Pay out redeemed shares of a tokenized event
+///
+/// Fees are charged as on `claim_winnings`, with the creator's cut left in
+/// the vault it was charged from.
+fn pay_redemption(accounts: &RedeemShares, outcome: Outcome, amount: u64) -> Result<Payout> {
+    let event = &accounts.event;
+    let token_program = &accounts.token_program;
+    let total_pool = event.doom_pool.saturating_add(event.life_pool);
+    let ((vault, to, treasury), (other_vault, other_to, other_treasury)) = match outcome {
+        Outcome::Doom => (
+            (
+                accounts.doom_vault.to_account_info(),
+                accounts.holder_doom_account.to_account_info(),
+                accounts.doom_treasury.to_account_info(),
+            ),
+            (
+                accounts.life_vault.to_account_info(),
+                accounts.holder_life_account.to_account_info(),
+                accounts.life_treasury.to_account_info(),
+            ),
+        ),
+        _ => (
+            (
+                accounts.life_vault.to_account_info(),
+                accounts.holder_life_account.to_account_info(),
+                accounts.life_treasury.to_account_info(),
+            ),
+            (
+                accounts.doom_vault.to_account_info(),
+                accounts.holder_doom_account.to_account_info(),
+                accounts.doom_treasury.to_account_info(),
+            ),
+        ),
+    };
+    let mut payout = Payout {
+        stake: amount,
+        returned: amount,
+        winnings: 0,
+        doom_fee: 0,
+        life_fee: 0,
+        creator_doom_fee: 0,
+        creator_life_fee: 0,
+        winning_pool: total_pool,
+    };
+
+    let refundable = matches!(event.status, EventStatus::Cancelled | EventStatus::Expired)
+        || (event.status == EventStatus::Resolved && event.outcome == Some(Outcome::Invalid));
+    if refundable {
+        transfer_from_vault(token_program, vault, to, event, amount)?;
+        return Ok(payout);
+    }
+
+    require!(
+        event.status == EventStatus::Resolved,
+        PredictionError::EventNotResolved
+    );
+    let creator_fee_bps = event.creator_fee_bps;
+    let (fee_outcome, fee, creator_fee) = match event.outcome {
+        Some(Outcome::Draw) => {
+            let (returned, fee, creator_fee) = split_fee(amount, event.fee_basis_points, creator_fee_bps)?;
+            transfer_from_vault(token_program, vault.clone(), to, event, returned)?;
+            transfer_from_vault(token_program, vault, treasury, event, fee)?;
+            payout.returned = returned;
+            (outcome, fee, creator_fee)
+        }
+        Some(winning_outcome) if winning_outcome == outcome => {
+            let (winning_pool, losing_pool) = match outcome {
+                Outcome::Doom => (event.doom_pool, event.life_pool),
+                _ => (event.life_pool, event.doom_pool),
+            };
+            // share = shares / winning_pool * losing_pool
+            let share = pro_rata(amount, losing_pool, winning_pool)?;
+            let (winnings, fee, creator_fee) = split_fee(share, event.fee_basis_points, creator_fee_bps)?;
+            transfer_from_vault(token_program, vault, to, event, amount)?;
+            transfer_from_vault(token_program, other_vault.clone(), other_to, event, winnings)?;
+            transfer_from_vault(token_program, other_vault, other_treasury, event, fee)?;
+            payout.winnings = winnings;
+            payout.winning_pool = winning_pool;
+            let other_outcome = if outcome == Outcome::Doom { Outcome::Life } else { Outcome::Doom };
+            (other_outcome, fee, creator_fee)
+        }
+        _ => return err!(PredictionError::NotAWinner),
+    };
+
+    if fee_outcome == Outcome::Doom {
+        payout.doom_fee = fee;
+        payout.creator_doom_fee = creator_fee;
+    } else {
+        payout.life_fee = fee;
+        payout.creator_life_fee = creator_fee;
+    }
+    Ok(payout)
+}
+
+/// Close a swept event once its last bet and oracle vote are closed,
+/// returning its rent to the creator
+fn close_swept_event<'info>(
+    event: &Account<'info, PredictionEvent>,
+    creator: &SystemAccount<'info>,
+) -> Result<()> {
+    if event.status == EventStatus::Swept && event.accounts_closed() {
+        event.close(creator.to_account_info())?;
+        msg!("Swept event {} closed", event.event_id);
+    }
+    Ok(())
+}
+
+/// Sweep what's left in an event vault to the treasury and close it,
+/// returning the amount swept
+fn sweep_vault<'info>(
+    token_program: &Program<'info, Token>,
+    vault: AccountInfo<'info>,
+    treasury: AccountInfo<'info>,
+    rent_to: AccountInfo<'info>,
+    event: &Account<'info, PredictionEvent>,
+) -> Result<u64> {
+    let amount = TokenAccount::try_deserialize(&mut &vault.try_borrow_data()?[..])?.amount;
+    transfer_from_vault(token_program, vault.clone(), treasury, event, amount)?;
+
+    let event_id = event.event_id.to_le_bytes();
+    let seeds = &[b"event".as_ref(), event_id.as_ref(), &[event.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let close = CloseAccount {
+        account: vault,
+        destination: rent_to,
+        authority: event.to_account_info(),
+    };
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        close,
+        signer_seeds,
+    ))?;
+    Ok(amount)
+}
+
+/// Aggregate price read from a Pyth-compatible price account
+pub struct PriceData {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+/// Read the aggregate price from a price account in the Pyth v2 layout
+fn load_price_feed(account: &AccountInfo) -> Result<PriceData> {
+    const MAGIC: u32 = 0xa1b2c3d4;
+    const ACCOUNT_TYPE_PRICE: u32 = 3;
+    const STATUS_TRADING: u32 = 1;
+
+    require_keys_eq!(*account.owner, pyth::ID, PredictionError::InvalidPriceFeed);
+    let data = account.try_borrow_data()?;
+    require!(data.len() >= 240, PredictionError::InvalidPriceFeed);
+
+    let read_u32 = |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+    let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+    require!(
+        read_u32(0) == MAGIC && read_u32(8) == ACCOUNT_TYPE_PRICE,
+        PredictionError::InvalidPriceFeed
+    );
+    require!(
+        read_u32(224) == STATUS_TRADING,
+        PredictionError::PriceUnavailable
+    );
+
+    Ok(PriceData {
+        price: read_u64(208) as i64,
+        conf: read_u64(216),
+        expo: read_u32(20) as i32,
+        publish_time: read_u64(96) as i64,
+    })
+}
+
+/// Transfer tokens out of the bond vault or a treasury, signing as the platform config PDA
+fn transfer_from_platform_vault<'info>(
+    token_program: &Program<'info, Token>,
+    vault: &Account<'info, TokenAccount>,
+    to: AccountInfo<'info>,
+    platform_config: &Account<'info, PlatformConfig>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let seeds = &[b"platform_config".as_ref(), &[platform_config.bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let transfer = Transfer {
+        from: vault.to_account_info(),
+        to,
+        authority: platform_config.to_account_info(),
+    };
+    token::transfer(
+        CpiContext::new_with_signer(token_program.to_account_info(), transfer, signer_seeds),
+        amount,
+    )
+}
+
+// Account Contexts
+#[derive(Accounts)]
+pub struct InitializePlatform<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PlatformConfig::INIT_SPACE,
+        seeds = [b"platform_config"],
+        bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    /// DOOM mint, used for dispute bonds
+    pub doom_mint: Account<'info, Mint>,
+    pub life_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        token::mint = doom_mint,
+        token::authority = platform_config,
+        seeds = [b"bond_vault"],
+        bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+    /// Platform fees collected in DOOM
+    #[account(
+        init,
+        payer = authority,
+        token::mint = doom_mint,
+        token::authority = platform_config,
+        seeds = [b"treasury", doom_mint.key().as_ref()],
+        bump
+    )]
+    pub doom_treasury: Account<'info, TokenAccount>,
+    /// Platform fees collected in LIFE
+    #[account(
+        init,
+        payer = authority,
+        token::mint = life_mint,
+        token::authority = platform_config,
+        seeds = [b"treasury", life_mint.key().as_ref()],
+        bump
+    )]
+    pub life_treasury: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+#[instruction(event_id: u64)]
 pub struct CreateEvent<'info> {
     #[account(
         mut,
         seeds = [b"platform_config"],
         bump = platform_config.bump,
-        constraint = !platform_config.paused @ PredictionError::PlatformPaused
+        constraint = !platform_config.is_paused(PAUSE_CREATION) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + PredictionEvent::INIT_SPACE,
+        seeds = [b"event", event_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(address = platform_config.doom_mint @ PredictionError::InvalidMint)]
+    pub doom_mint: Account<'info, Mint>,
+    #[account(address = platform_config.life_mint @ PredictionError::InvalidMint)]
+    pub life_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = creator,
+        token::mint = doom_mint,
+        token::authority = event,
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump
+    )]
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(
+        init,
+        payer = creator,
+        token::mint = life_mint,
+        token::authority = event,
+        seeds = [b"vault_life", event.key().as_ref()],
+        bump
+    )]
+    pub life_vault: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", creator.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    /// Required when binding the event to a resolver
+    #[account(
+        seeds = [b"resolver_allowlist"],
+        bump = resolver_allowlist.bump
+    )]
+    pub resolver_allowlist: Option<Account<'info, ResolverAllowlist>>,
+    /// Required when the platform charges a creation bond
+    #[account(
+        mut,
+        constraint = creator_doom_account.owner == creator.key() @ PredictionError::InvalidBondAccount
+    )]
+    pub creator_doom_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = platform_config.bond_vault_bump
+    )]
+    pub bond_vault: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct AddEventOutcomes<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        has_one = creator @ PredictionError::Unauthorized
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + EventOutcomes::INIT_SPACE,
+        seeds = [b"outcomes", event.key().as_ref()],
+        bump
+    )]
+    pub event_outcomes: Account<'info, EventOutcomes>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetScalarRange<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        has_one = creator @ PredictionError::Unauthorized
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateShareMints<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        has_one = creator @ PredictionError::Unauthorized
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump = event.doom_vault_bump
+    )]
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(
+        seeds = [b"vault_life", event.key().as_ref()],
+        bump = event.life_vault_bump
+    )]
+    pub life_vault: Account<'info, TokenAccount>,
+    #[account(address = doom_vault.mint)]
+    pub doom_mint: Account<'info, Mint>,
+    #[account(address = life_vault.mint)]
+    pub life_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = doom_mint.decimals,
+        mint::authority = event,
+        seeds = [b"share_doom", event.key().as_ref()],
+        bump
+    )]
+    pub doom_share_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = creator,
+        mint::decimals = life_mint.decimals,
+        mint::authority = event,
+        seeds = [b"share_life", event.key().as_ref()],
+        bump
+    )]
+    pub life_share_mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct EnableLmsr<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.fee_manager == fee_manager.key() @ PredictionError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump = event.doom_vault_bump
+    )]
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", doom_vault.mint.as_ref()],
+        bump = platform_config.doom_treasury_bump
+    )]
+    pub doom_treasury: Account<'info, TokenAccount>,
+    pub fee_manager: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SetPriceTrigger<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        has_one = creator @ PredictionError::Unauthorized
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    /// CHECK: owner and layout are validated when the price is read
+    pub price_feed: UncheckedAccount<'info>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct InitializeOutcomeVault<'info> {
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        seeds = [b"outcomes", event.key().as_ref()],
+        bump = event_outcomes.bump
+    )]
+    pub event_outcomes: Account<'info, EventOutcomes>,
+    #[account(
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump = event.doom_vault_bump
+    )]
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(address = doom_vault.mint)]
+    pub doom_mint: Account<'info, Mint>,
+    #[account(
+        init,
+        payer = payer,
+        token::mint = doom_mint,
+        token::authority = event,
+        seeds = [b"vault_outcome", event.key().as_ref(), &[index]],
+        bump
+    )]
+    pub outcome_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBet<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_BETTING) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserBet::INIT_SPACE,
+        seeds = [b"user_bet", event.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, constraint = user_doom_account.owner == user.key())]
+    pub user_doom_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = user_life_account.owner == user.key())]
+    pub user_life_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump = event.doom_vault_bump
+    )]
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_life", event.key().as_ref()],
+        bump = event.life_vault_bump
+    )]
+    pub life_vault: Account<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    /// Categorical events only
+    #[account(
+        mut,
+        seeds = [b"outcomes", event.key().as_ref()],
+        bump = event_outcomes.bump
+    )]
+    pub event_outcomes: Option<Account<'info, EventOutcomes>>,
+    /// Categorical events only: vault of the outcome being bet on
+    #[account(mut)]
+    pub outcome_vault: Option<Account<'info, TokenAccount>>,
+    /// Tokenized events only: share mint of the outcome being bet on
+    #[account(mut)]
+    pub share_mint: Option<Account<'info, Mint>>,
+    /// Tokenized events only: receives the minted shares
+    #[account(mut)]
+    pub user_share_account: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawBet<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_WITHDRAWALS) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        seeds = [b"user_bet", event.key().as_ref(), user.key().as_ref()],
+        bump = user_bet.bump,
+        has_one = user
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, constraint = user_doom_account.owner == user.key())]
+    pub user_doom_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = user_life_account.owner == user.key())]
+    pub user_life_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump = event.doom_vault_bump
+    )]
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_life", event.key().as_ref()],
+        bump = event.life_vault_bump
+    )]
+    pub life_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    /// Categorical events only
+    #[account(
+        mut,
+        seeds = [b"outcomes", event.key().as_ref()],
+        bump = event_outcomes.bump
+    )]
+    pub event_outcomes: Option<Account<'info, EventOutcomes>>,
+    /// Categorical events only: vault of the outcome being withdrawn from
+    #[account(mut)]
+    pub outcome_vault: Option<Account<'info, TokenAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveEvent<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_RESOLUTION) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    /// Resolved by its bound resolver if it has one, else the platform oracle
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = event.resolver.unwrap_or(platform_config.oracle) == oracle.key() @ PredictionError::UnauthorizedOracle,
+        constraint = !event.paused @ PredictionError::EventPaused,
+        constraint = event.price_feed.is_none() @ PredictionError::PriceTriggered
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        constraint = proposer_doom_account.owner == oracle.key() @ PredictionError::InvalidBondAccount
+    )]
+    pub proposer_doom_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = platform_config.bond_vault_bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+    pub oracle: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveFromPriceFeed<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_RESOLUTION) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    /// CHECK: must match the event's price feed; owner and layout are validated when the price is read
+    pub price_feed: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct DisputeResolution<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_RESOLUTION) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        constraint = disputer_doom_account.owner == disputer.key() @ PredictionError::InvalidBondAccount
+    )]
+    pub disputer_doom_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = platform_config.bond_vault_bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+    pub disputer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeResolution<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_RESOLUTION) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        constraint = proposer_doom_account.owner == event.proposer @ PredictionError::InvalidBondAccount
+    )]
+    pub proposer_doom_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = platform_config.bond_vault_bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct SettleDispute<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.moderator == authority.key() @ PredictionError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    /// DOOM account of whichever side the ruling favours
+    #[account(mut)]
+    pub winner_doom_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = platform_config.bond_vault_bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+    /// Event moderator
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseBetting<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, PredictionEvent>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireEvent<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_RESOLUTION) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    /// Bond refund accounts, required when expiring a bonded dispute
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = platform_config.bond_vault_bump
+    )]
+    pub bond_vault: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = proposer_doom_account.owner == event.proposer @ PredictionError::InvalidBondAccount
+    )]
+    pub proposer_doom_account: Option<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = Some(disputer_doom_account.owner) == event.disputer @ PredictionError::InvalidBondAccount
+    )]
+    pub disputer_doom_account: Option<Account<'info, TokenAccount>>,
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimWinnings<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_CLAIMS) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        seeds = [b"user_bet", event.key().as_ref(), user.key().as_ref()],
+        bump = user_bet.bump,
+        has_one = user
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, constraint = user_doom_account.owner == user.key())]
+    pub user_doom_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = user_life_account.owner == user.key())]
+    pub user_life_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump = event.doom_vault_bump
+    )]
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_life", event.key().as_ref()],
+        bump = event.life_vault_bump
+    )]
+    pub life_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", doom_vault.mint.as_ref()],
+        bump = platform_config.doom_treasury_bump
+    )]
+    pub doom_treasury: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", life_vault.mint.as_ref()],
+        bump = platform_config.life_treasury_bump
+    )]
+    pub life_treasury: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    /// Categorical events only
+    #[account(
+        seeds = [b"outcomes", event.key().as_ref()],
+        bump = event_outcomes.bump
+    )]
+    pub event_outcomes: Option<Account<'info, EventOutcomes>>,
+}
+
+#[derive(Accounts)]
+pub struct SettleLosingBet<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_CLAIMS) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        seeds = [b"user_bet", event.key().as_ref(), user_bet.user.as_ref()],
+        bump = user_bet.bump
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(
+        mut,
+        seeds = [b"user_stats", user_bet.user.as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateResolverAllowlist<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.authority == authority.key() @ PredictionError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ResolverAllowlist::INIT_SPACE,
+        seeds = [b"resolver_allowlist"],
+        bump
+    )]
+    pub resolver_allowlist: Account<'info, ResolverAllowlist>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetOracleCommittee<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.authority == authority.key() @ PredictionError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + OracleCommittee::INIT_SPACE,
+        seeds = [b"oracle_committee"],
+        bump
+    )]
+    pub oracle_committee: Account<'info, OracleCommittee>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitOracleVote<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_RESOLUTION) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        seeds = [b"oracle_committee"],
+        bump = oracle_committee.bump
+    )]
+    pub oracle_committee: Account<'info, OracleCommittee>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused,
+        constraint = event.resolver.is_none() @ PredictionError::UnauthorizedOracle,
+        constraint = event.price_feed.is_none() @ PredictionError::PriceTriggered
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        init,
+        payer = member,
+        space = 8 + OracleVote::INIT_SPACE,
+        seeds = [b"oracle_vote", event.key().as_ref(), member.key().as_ref()],
+        bump
+    )]
+    pub oracle_vote: Account<'info, OracleVote>,
+    #[account(mut)]
+    pub member: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseOracleVote<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        has_one = creator
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        seeds = [b"oracle_vote", event.key().as_ref(), member.key().as_ref()],
+        bump = oracle_vote.bump,
+        has_one = event,
+        has_one = member,
+        close = member
+    )]
+    pub oracle_vote: Account<'info, OracleVote>,
+    /// Receives the vote's rent
+    #[account(mut)]
+    pub member: SystemAccount<'info>,
+    /// Receives the rent of a swept event once this is its last open account
+    #[account(mut)]
+    pub creator: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelEvent<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.moderator == authority.key() @ PredictionError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    /// Event moderator
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CancelEventForAbuse<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.moderator == authority.key() @ PredictionError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = platform_config.bond_vault_bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", bond_vault.mint.as_ref()],
+        bump = platform_config.doom_treasury_bump
+    )]
+    pub doom_treasury: Account<'info, TokenAccount>,
+    /// Event moderator
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ReturnCreationBond<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_WITHDRAWALS) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        constraint = creator_doom_account.owner == event.creator @ PredictionError::InvalidBondAccount
+    )]
+    pub creator_doom_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"bond_vault"],
+        bump = platform_config.bond_vault_bump
+    )]
+    pub bond_vault: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_CLAIMS) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        has_one = creator @ PredictionError::Unauthorized,
+        constraint = !event.paused @ PredictionError::EventPaused
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(mut, constraint = creator_doom_account.owner == creator.key())]
+    pub creator_doom_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = creator_life_account.owner == creator.key())]
+    pub creator_life_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump = event.doom_vault_bump
+    )]
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_life", event.key().as_ref()],
+        bump = event.life_vault_bump
+    )]
+    pub life_vault: Account<'info, TokenAccount>,
+    pub creator: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRefund<'info> {
+    #[account(
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_CLAIMS) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        seeds = [b"user_bet", event.key().as_ref(), user.key().as_ref()],
+        bump = user_bet.bump,
+        has_one = user
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut, constraint = user_doom_account.owner == user.key())]
+    pub user_doom_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = user_life_account.owner == user.key())]
+    pub user_life_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump = event.doom_vault_bump
+    )]
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_life", event.key().as_ref()],
+        bump = event.life_vault_bump
+    )]
+    pub life_vault: Account<'info, TokenAccount>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    /// Categorical events only
+    #[account(
+        seeds = [b"outcomes", event.key().as_ref()],
+        bump = event_outcomes.bump
+    )]
+    pub event_outcomes: Option<Account<'info, EventOutcomes>>,
+}
+
+#[derive(Accounts)]
+pub struct RedeemShares<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = !platform_config.is_paused(PAUSE_CLAIMS) @ PredictionError::PlatformPaused
     )]
     pub platform_config: Account<'info, PlatformConfig>,
     #[account(
-        init,
-        payer = creator,
-        space = 8 + PredictionEvent::INIT_SPACE,
-        seeds = [b"event", event_id.to_le_bytes().as_ref()],
-        bump
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        constraint = !event.paused @ PredictionError::EventPaused
     )]
     pub event: Account<'info, PredictionEvent>,
+    /// Share mint of the side being redeemed
     #[account(mut)]
-    pub creator: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub share_mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        constraint = holder_share_account.mint == share_mint.key() @ PredictionError::InvalidShareMint,
+        constraint = holder_share_account.owner == holder.key()
+    )]
+    pub holder_share_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = holder_doom_account.owner == holder.key())]
+    pub holder_doom_account: Account<'info, TokenAccount>,
+    #[account(mut, constraint = holder_life_account.owner == holder.key())]
+    pub holder_life_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump = event.doom_vault_bump
+    )]
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_life", event.key().as_ref()],
+        bump = event.life_vault_bump
+    )]
+    pub life_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", doom_vault.mint.as_ref()],
+        bump = platform_config.doom_treasury_bump
+    )]
+    pub doom_treasury: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", life_vault.mint.as_ref()],
+        bump = platform_config.life_treasury_bump
+    )]
+    pub life_treasury: Account<'info, TokenAccount>,
+    pub holder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct PlaceBet<'info> {
+pub struct CloseUserBet<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        has_one = creator
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    #[account(
+        mut,
+        seeds = [b"user_bet", event.key().as_ref(), user.key().as_ref()],
+        bump = user_bet.bump,
+        has_one = event,
+        has_one = user,
+        close = user
+    )]
+    pub user_bet: Account<'info, UserBet>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    /// Receives the rent of a swept event once this is its last open account
+    #[account(mut)]
+    pub creator: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseEvent<'info> {
     #[account(
         mut,
         seeds = [b"platform_config"],
-        bump = platform_config.bump,
-        constraint = !platform_config.paused @ PredictionError::PlatformPaused
+        bump = platform_config.bump
     )]
     pub platform_config: Account<'info, PlatformConfig>,
     #[account(
         mut,
         seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
-        bump = event.bump
+        bump = event.bump,
+        has_one = creator
     )]
     pub event: Account<'info, PredictionEvent>,
+    /// Receives the rent of the event and its vaults
+    #[account(mut)]
+    pub creator: SystemAccount<'info>,
     #[account(
-        init,
-        payer = user,
-        space = 8 + UserBet::INIT_SPACE,
-        seeds = [b"user_bet", event.key().as_ref(), user.key().as_ref()],
-        bump
+        mut,
+        seeds = [b"vault_doom", event.key().as_ref()],
+        bump = event.doom_vault_bump
     )]
-    pub user_bet: Account<'info, UserBet>,
-    #[account(mut)]
-    pub user: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    pub doom_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"vault_life", event.key().as_ref()],
+        bump = event.life_vault_bump
+    )]
+    pub life_vault: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", doom_vault.mint.as_ref()],
+        bump = platform_config.doom_treasury_bump
+    )]
+    pub doom_treasury: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        seeds = [b"treasury", life_vault.mint.as_ref()],
+        bump = platform_config.life_treasury_bump
+    )]
+    pub life_treasury: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+    /// Categorical events only
+    #[account(
+        mut,
+        seeds = [b"outcomes", event.key().as_ref()],
+        bump = event_outcomes.bump,
+        close = creator
+    )]
+    pub event_outcomes: Option<Account<'info, EventOutcomes>>,
 }
 
 #[derive(Accounts)]
-pub struct ResolveEvent<'info> {
+pub struct WithdrawFees<'info> {
     #[account(
         seeds = [b"platform_config"],
         bump = platform_config.bump,
-        constraint = platform_config.oracle == oracle.key() @ PredictionError::UnauthorizedOracle
+        constraint = platform_config.fee_manager == authority.key() @ PredictionError::Unauthorized,
+        constraint = !platform_config.is_paused(PAUSE_WITHDRAWALS) @ PredictionError::PlatformPaused
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    /// Mint of the treasury being withdrawn from
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"treasury", mint.key().as_ref()],
+        bump
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    #[account(mut, token::mint = mint)]
+    pub destination: Account<'info, TokenAccount>,
+    /// Fee manager
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePlatform<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
     )]
     pub platform_config: Account<'info, PlatformConfig>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetEventBetLimits<'info> {
     #[account(
         mut,
         seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
-        bump = event.bump
+        bump = event.bump,
+        has_one = creator @ PredictionError::Unauthorized
     )]
     pub event: Account<'info, PredictionEvent>,
-    pub oracle: Signer<'info>,
+    pub creator: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct CancelEvent<'info> {
+pub struct SetBettingClosesAt<'info> {
+    #[account(
+        mut,
+        seeds = [b"event", event.event_id.to_le_bytes().as_ref()],
+        bump = event.bump,
+        has_one = creator @ PredictionError::Unauthorized
+    )]
+    pub event: Account<'info, PredictionEvent>,
+    pub creator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetEventPaused<'info> {
     #[account(
         seeds = [b"platform_config"],
         bump = platform_config.bump,
-        constraint = platform_config.authority == authority.key() @ PredictionError::Unauthorized
+        constraint = platform_config.pauser == pauser.key() @ PredictionError::Unauthorized
     )]
     pub platform_config: Account<'info, PlatformConfig>,
     #[account(
@@ -298,11 +4030,45 @@ pub struct CancelEvent<'info> {
         bump = event.bump
     )]
     pub event: Account<'info, PredictionEvent>,
+    pub pauser: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyPendingConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.authority == authority.key() @ PredictionError::Unauthorized
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
     pub authority: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct UpdatePlatform<'info> {
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump,
+        constraint = platform_config.pending_authority == Some(new_authority.key()) @ PredictionError::NotPendingAuthority
+    )]
+    pub platform_config: Account<'info, PlatformConfig>,
+    pub new_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetRoles<'info> {
     #[account(
         mut,
         seeds = [b"platform_config"],
@@ -317,17 +4083,63 @@ pub struct UpdatePlatform<'info> {
 #[account]
 #[derive(InitSpace)]
 pub struct PlatformConfig {
+    /// Admin: oracle, resolvers, dispute settings, roles and its own transfer
     pub authority: Pubkey,
+    /// Proposed authority, until it accepts the transfer
+    pub pending_authority: Option<Pubkey>,
+    /// Can pause and unpause the platform and individual events
+    pub pauser: Pubkey,
+    /// Sets fees and the creation bond, and withdraws from the treasury
+    pub fee_manager: Pubkey,
+    /// Cancels events and settles disputes
+    pub moderator: Pubkey,
     pub oracle: Pubkey,
+    /// Mints every event's vaults are created for
+    pub doom_mint: Pubkey,
+    pub life_mint: Pubkey,
     pub fee_basis_points: u16,
-    pub paused: bool,
+    /// Seconds queued fee, oracle and creator fee changes wait before they can be applied
+    pub config_delay: i64,
+    pub pending_fee_basis_points: Option<u16>,
+    pub pending_oracle: Option<Pubkey>,
+    pub pending_config_delay: Option<i64>,
+    pub pending_creator_fee_bps: Option<u16>,
+    pub pending_config_effective_at: i64,
+    /// `PAUSE_*` flags for the operations currently halted
+    pub paused: u8,
     pub total_doom_fees: u64,
     pub total_life_fees: u64,
     pub total_events: u64,
     pub total_bets: u64,
+    /// Seconds an outcome proposal stays open to disputes, 0 to resolve instantly
+    pub dispute_window: i64,
+    /// DOOM bond posted by both the proposer and any disputer
+    pub dispute_bond: u64,
+    /// DOOM bond escrowed by event creators
+    pub creation_bond: u64,
+    /// Share of the platform fee paid to event creators, in basis points of the fee
+    pub creator_fee_bps: u16,
+    /// Bet limits copied into new events
+    pub bet_limits: BetLimits,
+    /// Seconds before the deadline that betting closes on new events
+    pub betting_cutoff: i64,
+    /// Share of a withdrawn stake kept in the pool on new events, in basis points
+    pub exit_penalty_bps: u16,
+    /// Seconds after the resolution deadline that new events can be swept
+    pub sweep_window: i64,
+    pub bond_vault_bump: u8,
+    pub doom_treasury_bump: u8,
+    pub life_treasury_bump: u8,
     pub bump: u8,
 }
 
+impl PlatformConfig {
+    /// Check whether any of the given `PAUSE_*` flags is set
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.paused & flags != 0
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct PredictionEvent {
@@ -338,14 +4150,238 @@ pub struct PredictionEvent {
     #[max_len(512)]
     pub description: String,
     pub deadline: i64,
+    /// No bets are accepted from this time on, at or before the deadline
+    pub betting_closes_at: i64,
     pub resolution_deadline: i64,
+    /// Resolver bound at creation, overriding the platform oracle
+    pub resolver: Option<Pubkey>,
+    /// Platform fee when the event was created, charged on its claims
+    pub fee_basis_points: u16,
+    /// Creators' share of the fee when the event was created
+    pub creator_fee_bps: u16,
+    /// Limits checked on every bet
+    pub bet_limits: BetLimits,
+    /// Share of a withdrawn stake kept in the pool, in basis points
+    pub exit_penalty_bps: u16,
+    /// Seconds after the resolution deadline that the event can be closed
+    /// with bets still open, sweeping its vaults
+    pub sweep_window: i64,
+    /// Halts betting, resolution and claims on this event alone
+    pub paused: bool,
     pub status: EventStatus,
+    pub kind: EventKind,
+    /// 2 for binary and scalar events, number of named outcomes for categorical events
+    pub outcome_count: u8,
+    /// Scalar events only: range the resolved value is interpolated over
+    pub lower_bound: i64,
+    pub upper_bound: i64,
+    /// Price-triggered events only: price account and trigger condition
+    pub price_feed: Option<Pubkey>,
+    pub price_threshold: i64,
+    pub price_expo: i32,
+    pub price_comparator: PriceComparator,
     pub outcome: Option<Outcome>,
     pub doom_pool: u64,
     pub life_pool: u64,
+    /// Exit penalties from withdrawn bets, paid out pro-rata with the winnings
+    pub doom_penalty_pool: u64,
+    pub life_penalty_pool: u64,
     pub total_bettors: u32,
+    /// Bet accounts not yet closed with `close_user_bet`
+    pub outstanding_bets: u32,
     pub created_at: i64,
     pub resolved_at: Option<i64>,
+    /// Oracle that proposed the outcome under dispute resolution
+    pub proposer: Pubkey,
+    pub disputer: Option<Pubkey>,
+    pub dispute_ends_at: i64,
+    /// Bond posted by the proposer, and matched by any disputer
+    pub bond: u64,
+    /// Outcome oracle committee members have voted for so far
+    pub committee_outcome: Option<Outcome>,
+    pub committee_votes: u8,
+    /// Oracle vote accounts not yet closed with `close_oracle_vote`
+    pub oracle_votes: u8,
+    /// Stake paid back out through claims and refunds
+    pub settled_stake: u64,
+    /// Creation bond still escrowed in the bond vault
+    pub creation_bond: u64,
+    /// Creator's unclaimed cut of the fees, held in the event vaults
+    pub creator_doom_fees: u64,
+    pub creator_life_fees: u64,
+    /// Positions are held as DOOM and LIFE share tokens, redeemed with `redeem_shares`
+    pub tokenized: bool,
+    pub pricing: PricingMode,
+    /// LMSR liquidity parameter `b`, in DOOM
+    pub lmsr_liquidity: u64,
+    /// DOOM paid for LMSR shares, refunded if the event doesn't resolve
+    pub lmsr_collected: u64,
+    pub doom_vault_bump: u8,
+    pub life_vault_bump: u8,
+    /// Share mint bumps, 0 unless the event is tokenized
+    pub doom_share_mint_bump: u8,
+    pub life_share_mint_bump: u8,
+    pub bump: u8,
+}
+
+impl PredictionEvent {
+    /// Check that an outcome can be bet on or resolved to for this event
+    pub fn validate_outcome(&self, outcome: Outcome) -> Result<()> {
+        let valid = match (self.kind, outcome) {
+            (EventKind::Binary | EventKind::Scalar, Outcome::Doom | Outcome::Life) => true,
+            (EventKind::Categorical, Outcome::Categorical(index)) => index < self.outcome_count,
+            _ => false,
+        };
+        require!(valid, PredictionError::InvalidOutcome);
+        Ok(())
+    }
+
+    /// Check that an event can be resolved to an outcome
+    ///
+    /// Any event can resolve Invalid or Draw.
+    pub fn validate_resolution(&self, outcome: Outcome) -> Result<()> {
+        match (self.kind, outcome) {
+            (_, Outcome::Invalid | Outcome::Draw) | (EventKind::Scalar, Outcome::Scalar(_)) => Ok(()),
+            (EventKind::Scalar, _) => err!(PredictionError::InvalidOutcome),
+            _ => self.validate_outcome(outcome),
+        }
+    }
+
+    /// Long weight, short weight and their sum for a resolved scalar value
+    ///
+    /// With no bets on one side there is no counterparty, so both sides simply
+    /// get their stake back.
+    pub fn scalar_weights(&self, value: i64) -> (u64, u64, u64) {
+        if self.doom_pool == 0 || self.life_pool == 0 {
+            return (1, 1, 1);
+        }
+        let value = value.clamp(self.lower_bound, self.upper_bound);
+        let long_weight = (value as i128 - self.lower_bound as i128) as u64;
+        let short_weight = (self.upper_bound as i128 - value as i128) as u64;
+        (long_weight, short_weight, long_weight + short_weight)
+    }
+
+    /// Address of the share mint for one side of a tokenized event
+    pub fn share_mint_address(&self, event: &Pubkey, outcome: Outcome) -> Result<Pubkey> {
+        require!(self.tokenized, PredictionError::InvalidShareMint);
+        let (seed, bump) = match outcome {
+            Outcome::Doom => (b"share_doom".as_ref(), self.doom_share_mint_bump),
+            Outcome::Life => (b"share_life".as_ref(), self.life_share_mint_bump),
+            _ => return err!(PredictionError::InvalidOutcome),
+        };
+        Pubkey::create_program_address(&[seed, event.as_ref(), &[bump]], &crate::ID)
+            .map_err(|_| error!(PredictionError::InvalidShareMint))
+    }
+
+    /// Whether every bet and oracle vote account of the event has been closed
+    pub fn accounts_closed(&self) -> bool {
+        self.outstanding_bets == 0 && self.oracle_votes == 0
+    }
+
+    /// Stake of a bet that is owed a payout on this resolved event
+    pub fn claimable_stake(&self, user_bet: &UserBet) -> u64 {
+        match self.outcome {
+            Some(Outcome::Scalar(value)) => {
+                let (long_weight, short_weight, _) = self.scalar_weights(value);
+                let long = if long_weight > 0 { user_bet.doom_amount } else { 0 };
+                let short = if short_weight > 0 { user_bet.life_amount } else { 0 };
+                long.saturating_add(short)
+            }
+            Some(Outcome::Draw) => user_bet.total_stake(),
+            Some(outcome) => user_bet.stake_on(outcome),
+            None => 0,
+        }
+    }
+}
+
+/// Named outcomes and per-outcome pools of a categorical event
+#[account]
+#[derive(InitSpace)]
+pub struct EventOutcomes {
+    pub event: Pubkey,
+    #[max_len(16, 32)]
+    pub names: Vec<String>,
+    #[max_len(16)]
+    pub pools: Vec<u64>,
+    /// Bump of each outcome vault, 0 until the vault is initialized
+    #[max_len(16)]
+    pub vault_bumps: Vec<u8>,
+    pub bump: u8,
+}
+
+impl EventOutcomes {
+    pub fn total_pool(&self) -> u64 {
+        self.pools.iter().fold(0, |total, pool| total.saturating_add(*pool))
+    }
+
+    /// Address of an initialized outcome vault
+    pub fn vault_address(&self, event: &Pubkey, index: u8) -> Result<Pubkey> {
+        let bump = self
+            .vault_bumps
+            .get(index as usize)
+            .copied()
+            .filter(|bump| *bump != 0)
+            .ok_or(PredictionError::InvalidOutcomeVault)?;
+        Pubkey::create_program_address(
+            &[b"vault_outcome", event.as_ref(), &[index], &[bump]],
+            &crate::ID,
+        )
+        .map_err(|_| error!(PredictionError::InvalidOutcomeVault))
+    }
+
+    /// Validate that `accounts` starts with every outcome vault in outcome order
+    ///
+    /// Vaults that were never initialized hold nothing and are not checked.
+    pub fn outcome_vaults<'a, 'info>(
+        &self,
+        event: &Pubkey,
+        accounts: &'a [AccountInfo<'info>],
+    ) -> Result<&'a [AccountInfo<'info>]> {
+        require!(
+            accounts.len() >= self.pools.len(),
+            PredictionError::InvalidOutcomeVault
+        );
+        let vaults = &accounts[..self.pools.len()];
+        for (index, vault) in vaults.iter().enumerate() {
+            if self.vault_bumps[index] != 0 {
+                require_keys_eq!(
+                    vault.key(),
+                    self.vault_address(event, index as u8)?,
+                    PredictionError::InvalidOutcomeVault
+                );
+            }
+        }
+        Ok(vaults)
+    }
+}
+
+/// Resolvers events may be bound to instead of the platform oracle
+#[account]
+#[derive(InitSpace)]
+pub struct ResolverAllowlist {
+    #[max_len(32)]
+    pub resolvers: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+/// Oracles that resolve events by M-of-N vote
+#[account]
+#[derive(InitSpace)]
+pub struct OracleCommittee {
+    #[max_len(10)]
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub bump: u8,
+}
+
+/// A committee member's vote on an event outcome
+#[account]
+#[derive(InitSpace)]
+pub struct OracleVote {
+    pub event: Pubkey,
+    pub member: Pubkey,
+    pub outcome: Outcome,
+    pub voted_at: i64,
     pub bump: u8,
 }
 
@@ -354,40 +4390,307 @@ pub struct PredictionEvent {
 pub struct UserBet {
     pub event: Pubkey,
     pub user: Pubkey,
-    pub outcome: Outcome,
-    pub amount: u64,
+    pub doom_amount: u64,
+    pub life_amount: u64,
+    /// Stake per outcome on categorical events
+    #[max_len(16)]
+    pub outcome_amounts: Vec<u64>,
+    /// DOOM paid for shares on LMSR events, where stakes count shares
+    pub lmsr_cost: u64,
+    /// Time of the first bet on this position
     pub placed_at: i64,
     pub claimed: bool,
     pub refunded: bool,
+    /// Lost on a resolved event and counted in the user's stats
+    pub settled_loss: bool,
+    pub bump: u8,
+}
+
+impl UserBet {
+    pub fn stake_on(&self, outcome: Outcome) -> u64 {
+        match outcome {
+            Outcome::Doom => self.doom_amount,
+            Outcome::Life => self.life_amount,
+            Outcome::Categorical(index) => {
+                self.outcome_amounts.get(index as usize).copied().unwrap_or(0)
+            }
+            Outcome::Scalar(_) | Outcome::Invalid | Outcome::Draw => 0,
+        }
+    }
+
+    /// Tokens put into the position: its LMSR cost, or else its stake
+    pub fn wagered(&self) -> u64 {
+        if self.lmsr_cost > 0 {
+            self.lmsr_cost
+        } else {
+            self.total_stake()
+        }
+    }
+
+    pub fn total_stake(&self) -> u64 {
+        self.outcome_amounts
+            .iter()
+            .fold(self.doom_amount.saturating_add(self.life_amount), |total, stake| {
+                total.saturating_add(*stake)
+            })
+    }
+
+    pub fn add_stake(&mut self, outcome: Outcome, amount: u64, outcome_count: u8) -> Result<()> {
+        let stake = match outcome {
+            Outcome::Doom => &mut self.doom_amount,
+            Outcome::Life => &mut self.life_amount,
+            Outcome::Categorical(index) => {
+                if self.outcome_amounts.len() < outcome_count as usize {
+                    self.outcome_amounts.resize(outcome_count as usize, 0);
+                }
+                &mut self.outcome_amounts[index as usize]
+            }
+            Outcome::Scalar(_) | Outcome::Invalid | Outcome::Draw => {
+                return err!(PredictionError::InvalidOutcome)
+            }
+        };
+        *stake = stake.checked_add(amount).ok_or(PredictionError::Overflow)?;
+        Ok(())
+    }
+
+    pub fn remove_stake(&mut self, outcome: Outcome, amount: u64) -> Result<()> {
+        let stake = match outcome {
+            Outcome::Doom => &mut self.doom_amount,
+            Outcome::Life => &mut self.life_amount,
+            Outcome::Categorical(index) => self
+                .outcome_amounts
+                .get_mut(index as usize)
+                .ok_or(PredictionError::InsufficientStake)?,
+            Outcome::Scalar(_) | Outcome::Invalid | Outcome::Draw => {
+                return err!(PredictionError::InvalidOutcome)
+            }
+        };
+        *stake = stake.checked_sub(amount).ok_or(PredictionError::InsufficientStake)?;
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserStats {
+    pub user: Pubkey,
+    pub total_bets: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub total_wagered: u64,
+    pub total_won: u64,
+    pub total_lost: u64,
+    pub net_profit: i64,
+    pub events_created: u64,
+    pub first_bet_at: Option<i64>,
+    pub last_bet_at: Option<i64>,
+    /// Positive for a run of wins, negative for a run of losses
+    pub current_streak: i64,
+    pub best_streak: i64,
+    pub worst_streak: i64,
     pub bump: u8,
 }
 
+impl UserStats {
+    /// Set identity fields on an account freshly created by `init_if_needed`
+    pub fn init_if_new(&mut self, user: Pubkey, bump: u8) {
+        if self.user == Pubkey::default() {
+            self.user = user;
+            self.bump = bump;
+        }
+    }
+
+    pub fn record_win(&mut self, profit: u64) {
+        self.wins = self.wins.saturating_add(1);
+        self.total_won = self.total_won.saturating_add(profit);
+        self.net_profit = self.net_profit.saturating_add_unsigned(profit);
+        self.current_streak = if self.current_streak > 0 {
+            self.current_streak.saturating_add(1)
+        } else {
+            1
+        };
+        self.best_streak = self.best_streak.max(self.current_streak);
+    }
+
+    pub fn record_loss(&mut self, stake: u64) {
+        self.losses = self.losses.saturating_add(1);
+        self.total_lost = self.total_lost.saturating_add(stake);
+        self.net_profit = self.net_profit.saturating_sub_unsigned(stake);
+        self.current_streak = if self.current_streak < 0 {
+            self.current_streak.saturating_sub(1)
+        } else {
+            -1
+        };
+        self.worst_streak = self.worst_streak.min(self.current_streak);
+    }
+
+    /// Count an early-exit penalty as lost, without settling a bet
+    pub fn record_penalty(&mut self, penalty: u64) {
+        self.total_lost = self.total_lost.saturating_add(penalty);
+        self.net_profit = self.net_profit.saturating_sub_unsigned(penalty);
+    }
+}
+
+/// Limits on bet sizes, 0 meaning no limit except for `min_bet`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug, Default)]
+pub struct BetLimits {
+    /// Smallest single bet
+    pub min_bet: u64,
+    /// Largest single bet
+    pub max_bet: u64,
+    /// Largest total stake one user may hold on an event
+    pub max_user_stake: u64,
+    /// Largest share of the total pool one outcome may hold, in basis points
+    pub max_side_share_bps: u16,
+}
+
+impl BetLimits {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.min_bet > 0
+                && (self.max_bet == 0 || self.max_bet >= self.min_bet)
+                && (self.max_user_stake == 0 || self.max_user_stake >= self.min_bet)
+                && self.max_side_share_bps <= 10000,
+            PredictionError::InvalidBetLimits
+        );
+        Ok(())
+    }
+
+    /// Check a bet against the per-bet limits and the user's stake so far
+    pub fn check_bet(&self, amount: u64, user_stake: u64) -> Result<()> {
+        require!(amount >= self.min_bet, PredictionError::BetBelowMinimum);
+        require!(
+            self.max_bet == 0 || amount <= self.max_bet,
+            PredictionError::BetAboveMaximum
+        );
+        let user_stake = user_stake.checked_add(amount).ok_or(PredictionError::Overflow)?;
+        require!(
+            self.max_user_stake == 0 || user_stake <= self.max_user_stake,
+            PredictionError::UserStakeLimitExceeded
+        );
+        Ok(())
+    }
+
+    /// Check an outcome's pool against its cap once the bet is added
+    pub fn check_side_share(&self, side_pool: u64, total_pool: u64) -> Result<()> {
+        require!(
+            self.max_side_share_bps == 0
+                || side_pool as u128 * 10000 <= total_pool as u128 * self.max_side_share_bps as u128,
+            PredictionError::SideShareLimitExceeded
+        );
+        Ok(())
+    }
+}
+
 // Enums
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug, Default)]
 pub enum Outcome {
+    #[default]
     Doom,
     Life,
+    /// Index into the named outcomes of a categorical event
+    Categorical(u8),
+    /// Resolved value of a scalar event
+    Scalar(i64),
+    /// Question was ambiguous or malformed: every bet is refunded in full
+    Invalid,
+    /// No side won: every bet is returned less the platform fee
+    Draw,
 }
 
-impl Default for Outcome {
-    fn default() -> Self {
-        Outcome::Doom
-    }
+/// How a price must compare to the threshold for a price-triggered event to resolve Doom
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug, Default)]
+pub enum PriceComparator {
+    #[default]
+    Below,
+    Above,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug, Default)]
+pub enum EventKind {
+    /// Doom vs Life, staked in DOOM and LIFE respectively
+    #[default]
+    Binary,
+    /// Up to `MAX_OUTCOMES` named outcomes, all staked in DOOM
+    Categorical,
+    /// Numeric value in a range: Doom goes long, Life goes short
+    Scalar,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+/// How bets on an event are priced
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug, Default)]
+pub enum PricingMode {
+    /// Winners split the losing pool pro rata
+    #[default]
+    Parimutuel,
+    /// Binary shares bought from a logarithmic market scoring rule market
+    /// maker, each winning share paying a fixed 1 DOOM
+    Lmsr,
+}
+
+/// Event lifecycle
+///
+/// Active -> Closed -> Resolved | Cancelled | Expired -> Settled
+///
+/// Closed is optional: an Active event past its deadline can move straight to
+/// a final state. Settled is reached once every claim or refund has been paid.
+/// With a dispute window, resolution goes through Proposed -> [Disputed ->]
+/// Resolved. Conflicting oracle committee votes move an Active or Closed
+/// event straight to Disputed. A finished event closed after its sweep window
+/// with bets or votes still open is kept as Swept until they are closed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug, Default)]
 pub enum EventStatus {
+    #[default]
     Active,
     Resolved,
     Cancelled,
+    Expired,
+    Closed,
+    Settled,
+    Proposed,
+    Disputed,
+    Swept,
 }
 
-impl Default for EventStatus {
-    fn default() -> Self {
-        EventStatus::Active
+impl EventStatus {
+    /// Validate a lifecycle transition, returning the new status
+    pub fn transition(self, next: EventStatus) -> Result<EventStatus> {
+        use EventStatus::*;
+
+        match (self, next) {
+            (Active, Closed)
+            | (Active | Closed, Resolved | Cancelled | Expired | Proposed | Disputed)
+            | (Proposed, Disputed | Resolved)
+            | (Disputed, Resolved | Expired)
+            | (Resolved | Cancelled | Expired, Settled)
+            | (Resolved | Cancelled | Expired | Settled, Swept) => Ok(next),
+            (Closed, Closed) => err!(PredictionError::BettingAlreadyClosed),
+            (Proposed, _) => err!(PredictionError::ResolutionPending),
+            (Disputed, _) => err!(PredictionError::EventDisputed),
+            (Resolved, _) => err!(PredictionError::EventAlreadyResolved),
+            (Cancelled, _) => err!(PredictionError::EventAlreadyCancelled),
+            (Expired, _) => err!(PredictionError::EventAlreadyExpired),
+            (Settled, _) => err!(PredictionError::EventAlreadySettled),
+            (Swept, _) => err!(PredictionError::EventSwept),
+            (Active | Closed, Settled | Swept) => err!(PredictionError::EventNotFinalized),
+            (_, Active) => err!(PredictionError::InvalidStatusTransition),
+        }
     }
 }
 
+// Events
+/// Receipt for fees moved out of a treasury
+#[event]
+pub struct FeesWithdrawn {
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub authority: Pubkey,
+    pub amount: u64,
+    /// Treasury balance left after the withdrawal
+    pub remaining: u64,
+    pub timestamp: i64,
+}
+
 // Errors
 #[error_code]
 pub enum PredictionError {
@@ -417,4 +4720,162 @@ pub enum PredictionError {
     UnauthorizedOracle,
     #[msg("Unauthorized")]
     Unauthorized,
+    #[msg("Winnings already claimed")]
+    AlreadyClaimed,
+    #[msg("Bet did not win")]
+    NotAWinner,
+    #[msg("Event is not cancelled or expired")]
+    EventNotRefundable,
+    #[msg("Bet already refunded")]
+    AlreadyRefunded,
+    #[msg("Resolution deadline has passed")]
+    ResolutionDeadlinePassed,
+    #[msg("Resolution deadline has not passed")]
+    ResolutionDeadlineNotPassed,
+    #[msg("Event deadline not reached")]
+    DeadlineNotReached,
+    #[msg("Betting already closed")]
+    BettingAlreadyClosed,
+    #[msg("Event already cancelled")]
+    EventAlreadyCancelled,
+    #[msg("Event already expired")]
+    EventAlreadyExpired,
+    #[msg("Event already settled")]
+    EventAlreadySettled,
+    #[msg("Event not resolved, cancelled or expired")]
+    EventNotFinalized,
+    #[msg("Invalid event status transition")]
+    InvalidStatusTransition,
+    #[msg("Bet did not lose")]
+    BetNotLost,
+    #[msg("Invalid number of outcomes")]
+    InvalidOutcomeCount,
+    #[msg("Invalid outcome name")]
+    InvalidOutcomeName,
+    #[msg("Event already has bets")]
+    EventHasBets,
+    #[msg("Invalid outcome for this event")]
+    InvalidOutcome,
+    #[msg("Event outcomes account required")]
+    MissingEventOutcomes,
+    #[msg("Invalid outcome vault")]
+    InvalidOutcomeVault,
+    #[msg("Lower bound must be below upper bound")]
+    InvalidScalarRange,
+    #[msg("Invalid dispute window")]
+    InvalidDisputeWindow,
+    #[msg("Bond account does not belong to the expected owner")]
+    InvalidBondAccount,
+    #[msg("No resolution has been proposed")]
+    NoResolutionProposed,
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+    #[msg("Event is not disputed")]
+    EventNotDisputed,
+    #[msg("Resolution proposed and awaiting finalization")]
+    ResolutionPending,
+    #[msg("Event resolution is disputed")]
+    EventDisputed,
+    #[msg("Invalid oracle committee")]
+    InvalidCommittee,
+    #[msg("Not an oracle committee member")]
+    NotCommitteeMember,
+    #[msg("Event is not open for oracle votes")]
+    EventNotOpenForVotes,
+    #[msg("Resolver is not on the allowlist")]
+    ResolverNotAllowed,
+    #[msg("Resolver allowlist is full")]
+    ResolverAllowlistFull,
+    #[msg("Invalid price feed account")]
+    InvalidPriceFeed,
+    #[msg("Price feed does not match the event")]
+    PriceFeedMismatch,
+    #[msg("Price is not currently available")]
+    PriceUnavailable,
+    #[msg("Price was published before the event deadline")]
+    PriceBeforeDeadline,
+    #[msg("Price is stale")]
+    StalePrice,
+    #[msg("Price confidence interval is too wide")]
+    PriceConfidenceTooWide,
+    #[msg("Event resolved invalid; claim a refund instead")]
+    EventResolvedInvalid,
+    #[msg("Creator DOOM account and bond vault required for the creation bond")]
+    CreationBondRequired,
+    #[msg("Event has no creation bond to return")]
+    NoCreationBond,
+    #[msg("No creator fees to claim")]
+    NoCreatorFees,
+    #[msg("Invalid withdrawal amount")]
+    InvalidWithdrawAmount,
+    #[msg("Treasury balance too low")]
+    InsufficientTreasuryBalance,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    #[msg("Invalid config delay")]
+    InvalidConfigDelay,
+    #[msg("No config change is pending")]
+    NoPendingConfig,
+    #[msg("Pending config change is not yet effective")]
+    PendingConfigNotReady,
+    #[msg("Invalid pause flags")]
+    InvalidPauseFlags,
+    #[msg("Event is paused")]
+    EventPaused,
+    #[msg("Bet has not been claimed or refunded")]
+    BetNotSettled,
+    #[msg("Creation bond has not been returned")]
+    CreationBondNotReturned,
+    #[msg("Event has open bets or oracle votes, or unclaimed creator fees")]
+    EventNotSettled,
+    #[msg("Invalid bet limits")]
+    InvalidBetLimits,
+    #[msg("Bet limits cannot change once betting has started")]
+    BetLimitsLocked,
+    #[msg("Bet is below the event minimum")]
+    BetBelowMinimum,
+    #[msg("Bet is above the event maximum")]
+    BetAboveMaximum,
+    #[msg("Bet would exceed the per-user stake limit")]
+    UserStakeLimitExceeded,
+    #[msg("Bet would exceed the outcome's share of the pool")]
+    SideShareLimitExceeded,
+    #[msg("Betting has closed for this event")]
+    BettingClosed,
+    #[msg("Invalid betting cutoff")]
+    InvalidBettingCutoff,
+    #[msg("Withdrawal exceeds the stake on this outcome")]
+    InsufficientStake,
+    #[msg("Positions on this event are held as outcome shares")]
+    EventTokenized,
+    #[msg("Invalid share mint or share account")]
+    InvalidShareMint,
+    #[msg("Invalid LMSR liquidity")]
+    InvalidLiquidity,
+    #[msg("LMSR pricing is already enabled")]
+    LmsrAlreadyEnabled,
+    #[msg("Not supported on LMSR-priced events")]
+    LmsrUnsupported,
+    #[msg("Share price exceeds the maximum cost")]
+    MaxCostExceeded,
+    #[msg("Betting cutoff can only be moved earlier once bets are placed")]
+    BettingCutoffLocked,
+    #[msg("Price is published after the current time")]
+    PriceInFuture,
+    #[msg("Event was closed and its vaults swept")]
+    EventSwept,
+    #[msg("Event is still open for oracle votes")]
+    VotingOpen,
+    #[msg("Invalid sweep window")]
+    InvalidSweepWindow,
+    #[msg("Losing bet already settled")]
+    LossAlreadySettled,
+    #[msg("Event resolves from its price feed")]
+    PriceTriggered,
+    #[msg("Mint is not the platform's DOOM or LIFE mint")]
+    InvalidMint,
+    #[msg("Bond vault and both bond holders' DOOM accounts are required")]
+    DisputeBondAccountsRequired,
 }
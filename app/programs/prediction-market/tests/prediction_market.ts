@@ -11,16 +11,21 @@ import {
   PublicKey,
   Keypair,
   SystemProgram,
+  Transaction,
+  TransactionInstruction,
   LAMPORTS_PER_SOL,
 } from "@solana/web3.js";
 import {
   TOKEN_PROGRAM_ID,
   createMint,
   createAssociatedTokenAccount,
+  getOrCreateAssociatedTokenAccount,
   mintTo,
   getAccount,
+  transfer,
 } from "@solana/spl-token";
 import { expect } from "chai";
+import { createHash } from "crypto";
 import { PredictionMarket } from "../target/types/prediction_market";
 
 describe("prediction-market", () => {
@@ -42,9 +47,34 @@ describe("prediction-market", () => {
 
   // PDAs
   let platformConfigPDA: PublicKey;
+  let bondVaultPDA: PublicKey;
+  let doomTreasuryPDA: PublicKey;
+  let lifeTreasuryPDA: PublicKey;
+
+  // Oracle's DOOM account for resolution bonds
+  let oracleDoom: PublicKey;
 
   // Constants
   const FEE_BASIS_POINTS = 200; // 2%
+  const PAUSE_BETTING = 1 << 1;
+  const PAUSE_CLAIMS = 1 << 3;
+  const PAUSE_WITHDRAWALS = 1 << 4;
+  const PAUSE_ALL = 0x1f;
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  const findUserStats = (user: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from("user_stats"), user.toBuffer()],
+      program.programId
+    )[0];
+
+  // Fee and oracle changes are queued; with no config delay they apply at once
+  const applyPendingConfig = () =>
+    program.methods
+      .applyPendingConfig()
+      .accounts({ platformConfig: platformConfigPDA })
+      .rpc();
 
   before(async () => {
     // Generate keypairs
@@ -85,16 +115,37 @@ describe("prediction-market", () => {
       [Buffer.from("platform_config")],
       program.programId
     );
+    [bondVaultPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bond_vault")],
+      program.programId
+    );
+    [doomTreasuryPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), doomMint.toBuffer()],
+      program.programId
+    );
+    [lifeTreasuryPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury"), lifeMint.toBuffer()],
+      program.programId
+    );
+
+    oracleDoom = (await getOrCreateAssociatedTokenAccount(provider.connection, oracle, doomMint, oracle.publicKey)).address;
   });
 
   describe("initialize_platform", () => {
     it("initializes the platform config", async () => {
       await program.methods
-        .initializePlatform(FEE_BASIS_POINTS)
+        .initializePlatform(FEE_BASIS_POINTS, new anchor.BN(0))
         .accounts({
           platformConfig: platformConfigPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          bondVault: bondVaultPDA,
+          doomTreasury: doomTreasuryPDA,
+          lifeTreasury: lifeTreasuryPDA,
           authority: authority.publicKey,
           systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
         })
         .signers([authority])
         .rpc();
@@ -103,22 +154,37 @@ describe("prediction-market", () => {
 
       expect(config.authority.toString()).to.equal(authority.publicKey.toString());
       expect(config.oracle.toString()).to.equal(authority.publicKey.toString()); // Initially same as authority
+      expect(config.pendingAuthority).to.be.null;
+      for (const role of [config.pauser, config.feeManager, config.moderator]) {
+        expect(role.toString()).to.equal(authority.publicKey.toString());
+      }
+      expect(config.doomMint.toString()).to.equal(doomMint.toString());
+      expect(config.lifeMint.toString()).to.equal(lifeMint.toString());
       expect(config.feeBasisPoints).to.equal(FEE_BASIS_POINTS);
-      expect(config.paused).to.equal(false);
+      expect(config.configDelay.toNumber()).to.equal(0);
+      expect(config.paused).to.equal(0);
       expect(config.totalDoomFees.toNumber()).to.equal(0);
       expect(config.totalLifeFees.toNumber()).to.equal(0);
       expect(config.totalEvents.toNumber()).to.equal(0);
       expect(config.totalBets.toNumber()).to.equal(0);
+      expect(config.disputeWindow.toNumber()).to.equal(24 * 60 * 60);
     });
 
     it("fails to initialize twice", async () => {
       try {
         await program.methods
-          .initializePlatform(FEE_BASIS_POINTS)
+          .initializePlatform(FEE_BASIS_POINTS, new anchor.BN(0))
           .accounts({
             platformConfig: platformConfigPDA,
+            doomMint: doomMint,
+            lifeMint: lifeMint,
+            bondVault: bondVaultPDA,
+            doomTreasury: doomTreasuryPDA,
+            lifeTreasury: lifeTreasuryPDA,
             authority: authority.publicKey,
             systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
           })
           .signers([authority])
           .rpc();
@@ -138,13 +204,14 @@ describe("prediction-market", () => {
   describe("update_platform", () => {
     it("updates the oracle address", async () => {
       await program.methods
-        .updatePlatform(null, oracle.publicKey, null)
+        .updatePlatform(null, oracle.publicKey, null, null, null, null)
         .accounts({
           platformConfig: platformConfigPDA,
           authority: authority.publicKey,
         })
         .signers([authority])
         .rpc();
+      await applyPendingConfig();
 
       const config = await program.account.platformConfig.fetch(platformConfigPDA);
       expect(config.oracle.toString()).to.equal(oracle.publicKey.toString());
@@ -153,31 +220,33 @@ describe("prediction-market", () => {
     it("updates fee basis points", async () => {
       const newFee = 300; // 3%
       await program.methods
-        .updatePlatform(newFee, null, null)
+        .updatePlatform(newFee, null, null, null, null, null)
         .accounts({
           platformConfig: platformConfigPDA,
           authority: authority.publicKey,
         })
         .signers([authority])
         .rpc();
+      await applyPendingConfig();
 
       const config = await program.account.platformConfig.fetch(platformConfigPDA);
       expect(config.feeBasisPoints).to.equal(newFee);
 
       // Reset to original fee
       await program.methods
-        .updatePlatform(FEE_BASIS_POINTS, null, null)
+        .updatePlatform(FEE_BASIS_POINTS, null, null, null, null, null)
         .accounts({
           platformConfig: platformConfigPDA,
           authority: authority.publicKey,
         })
         .signers([authority])
         .rpc();
+      await applyPendingConfig();
     });
 
     it("can pause the platform", async () => {
       await program.methods
-        .updatePlatform(null, null, true)
+        .updatePlatform(null, null, PAUSE_ALL, null, null, null)
         .accounts({
           platformConfig: platformConfigPDA,
           authority: authority.publicKey,
@@ -186,23 +255,66 @@ describe("prediction-market", () => {
         .rpc();
 
       const config = await program.account.platformConfig.fetch(platformConfigPDA);
-      expect(config.paused).to.equal(true);
+      expect(config.paused).to.equal(PAUSE_ALL);
 
       // Unpause
       await program.methods
-        .updatePlatform(null, null, false)
+        .updatePlatform(null, null, 0, null, null, null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    });
+
+    it("updates the dispute window and bond", async () => {
+      // Resolve instantly unless a test opts into a dispute window
+      await program.methods
+        .updatePlatform(null, null, null, new anchor.BN(0), new anchor.BN(10 * 10 ** 9), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.disputeWindow.toNumber()).to.equal(0);
+      expect(config.disputeBond.toNumber()).to.equal(10 * 10 ** 9);
+    });
+
+    it("updates the creation bond and creator fee share", async () => {
+      let config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.creationBond.toNumber()).to.equal(10 * 10 ** 9);
+      expect(config.creatorFeeBps).to.equal(1000);
+
+      // Free creation and no creator cut unless a test opts in
+      await program.methods
+        .updateCreatorSettings(new anchor.BN(0), 0)
         .accounts({
           platformConfig: platformConfigPDA,
           authority: authority.publicKey,
         })
         .signers([authority])
         .rpc();
+
+      // The creator fee share is queued like fee changes
+      config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.creationBond.toNumber()).to.equal(0);
+      expect(config.creatorFeeBps).to.equal(1000);
+      expect(config.pendingCreatorFeeBps).to.equal(0);
+
+      await applyPendingConfig();
+      config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.creatorFeeBps).to.equal(0);
+      expect(config.pendingCreatorFeeBps).to.be.null;
     });
 
     it("fails when not authority", async () => {
       try {
         await program.methods
-          .updatePlatform(null, null, true)
+          .updatePlatform(null, null, PAUSE_ALL, null, null, null)
           .accounts({
             platformConfig: platformConfigPDA,
             authority: user1.publicKey,
@@ -257,7 +369,8 @@ describe("prediction-market", () => {
           "Will AI achieve AGI by 2030?",
           "Prediction on whether Artificial General Intelligence will be achieved.",
           new anchor.BN(deadline),
-          new anchor.BN(resolutionDeadline)
+          new anchor.BN(resolutionDeadline),
+          null
         )
         .accounts({
           platformConfig: platformConfigPDA,
@@ -271,6 +384,9 @@ describe("prediction-market", () => {
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
         })
         .signers([user1])
         .rpc();
@@ -284,6 +400,15 @@ describe("prediction-market", () => {
       expect(event.doomPool.toNumber()).to.equal(0);
       expect(event.lifePool.toNumber()).to.equal(0);
       expect(event.totalBettors.toNumber()).to.equal(0);
+
+      // Vaults are owned by the event PDA
+      const doomVault = await getAccount(provider.connection, doomVaultPDA);
+      const lifeVault = await getAccount(provider.connection, lifeVaultPDA);
+      expect(doomVault.owner.toString()).to.equal(eventPDA.toString());
+      expect(lifeVault.owner.toString()).to.equal(eventPDA.toString());
+      expect(doomVault.mint.toString()).to.equal(doomMint.toString());
+      expect(lifeVault.mint.toString()).to.equal(lifeMint.toString());
+      expect(Number(doomVault.amount)).to.equal(0);
     });
 
     it("fails with invalid deadline", async () => {
@@ -310,7 +435,8 @@ describe("prediction-market", () => {
             "Invalid Event",
             "This should fail",
             new anchor.BN(pastDeadline),
-            new anchor.BN(pastDeadline + 100)
+            new anchor.BN(pastDeadline + 100),
+            null
           )
           .accounts({
             platformConfig: platformConfigPDA,
@@ -324,6 +450,9 @@ describe("prediction-market", () => {
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            resolverAllowlist: null,
+            creatorDoomAccount: null,
+            bondVault: null,
           })
           .signers([user1])
           .rpc();
@@ -332,6 +461,57 @@ describe("prediction-market", () => {
         expect(error.message).to.include("InvalidDeadline");
       }
     });
+
+    it("fails with a mint other than the platform's", async () => {
+      const newEventId = new anchor.BN(98);
+      const [newEventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), newEventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const [newDoomVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_doom"), newEventPDA.toBuffer()],
+        program.programId
+      );
+      const [newLifeVault] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_life"), newEventPDA.toBuffer()],
+        program.programId
+      );
+      const foreignMint = await createMint(provider.connection, user1, user1.publicKey, null, 9);
+
+      const now = Math.floor(Date.now() / 1000);
+      try {
+        await program.methods
+          .createEvent(
+            newEventId,
+            "Foreign mint event",
+            "Pays out in a token the creator controls",
+            new anchor.BN(now + 86400),
+            new anchor.BN(now + 172800),
+            null
+          )
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: newEventPDA,
+            doomMint: foreignMint,
+            lifeMint: lifeMint,
+            doomVault: newDoomVault,
+            lifeVault: newLifeVault,
+            userStats: userStatsPDA,
+            creator: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            resolverAllowlist: null,
+            creatorDoomAccount: null,
+            bondVault: null,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidMint");
+      }
+    });
   });
 
   describe("place_bet", () => {
@@ -410,7 +590,7 @@ describe("prediction-market", () => {
       const betAmount = new anchor.BN(100 * 10 ** 9); // 100 DOOM
 
       await program.methods
-        .placeBet({ doom: {} }, betAmount)
+        .placeBet({ doom: {} }, betAmount, null)
         .accounts({
           platformConfig: platformConfigPDA,
           event: eventPDA,
@@ -423,14 +603,18 @@ describe("prediction-market", () => {
           user: user1.publicKey,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
         })
         .signers([user1])
         .rpc();
 
       // Verify bet was recorded
       const bet = await program.account.userBet.fetch(userBetPDA);
-      expect(bet.outcome).to.deep.equal({ doom: {} });
-      expect(bet.amount.toNumber()).to.equal(100 * 10 ** 9);
+      expect(bet.doomAmount.toNumber()).to.equal(100 * 10 ** 9);
+      expect(bet.lifeAmount.toNumber()).to.equal(0);
       expect(bet.claimed).to.equal(false);
 
       // Verify event pool was updated
@@ -441,32 +625,81 @@ describe("prediction-market", () => {
       // Verify vault received tokens
       const vaultAccount = await getAccount(provider.connection, doomVaultPDA);
       expect(Number(vaultAccount.amount)).to.equal(100 * 10 ** 9);
+
+      // Verify stats were initialized lazily
+      const stats = await program.account.userStats.fetch(userStatsPDA);
+      expect(stats.user.toString()).to.equal(user1.publicKey.toString());
+      expect(stats.totalBets.toNumber()).to.equal(1);
+      expect(stats.totalWagered.toNumber()).to.equal(100 * 10 ** 9);
+      expect(stats.eventsCreated.toNumber()).to.equal(1);
     });
 
-    it("fails to place duplicate bet", async () => {
-      try {
-        await program.methods
-          .placeBet({ doom: {} }, new anchor.BN(50 * 10 ** 9))
-          .accounts({
-            platformConfig: platformConfigPDA,
-            event: eventPDA,
-            userBet: userBetPDA,
-            userDoomAccount: user1DoomATA,
-            userLifeAccount: user1LifeATA,
-            doomVault: doomVaultPDA,
-            lifeVault: lifeVaultPDA,
-            userStats: userStatsPDA,
-            user: user1.publicKey,
-            systemProgram: SystemProgram.programId,
-            tokenProgram: TOKEN_PROGRAM_ID,
-          })
-          .signers([user1])
-          .rpc();
-        expect.fail("Should have thrown an error");
-      } catch {
-        // Expected to fail - account already initialized
-        // Error is expected, test passes
-      }
+    it("tops up an existing position", async () => {
+      await program.methods
+        .placeBet({ doom: {} }, new anchor.BN(50 * 10 ** 9), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: userBetPDA,
+          userDoomAccount: user1DoomATA,
+          userLifeAccount: user1LifeATA,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          userStats: userStatsPDA,
+          user: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user1])
+        .rpc();
+
+      const bet = await program.account.userBet.fetch(userBetPDA);
+      expect(bet.doomAmount.toNumber()).to.equal(150 * 10 ** 9);
+      expect(bet.lifeAmount.toNumber()).to.equal(0);
+
+      // Same bettor, so the bettor count is unchanged
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.doomPool.toNumber()).to.equal(150 * 10 ** 9);
+      expect(event.totalBettors.toNumber()).to.equal(1);
+    });
+
+    it("hedges an existing position on the other side", async () => {
+      await program.methods
+        .placeBet({ life: {} }, new anchor.BN(20 * 10 ** 9), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: userBetPDA,
+          userDoomAccount: user1DoomATA,
+          userLifeAccount: user1LifeATA,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          userStats: userStatsPDA,
+          user: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user1])
+        .rpc();
+
+      const bet = await program.account.userBet.fetch(userBetPDA);
+      expect(bet.doomAmount.toNumber()).to.equal(150 * 10 ** 9);
+      expect(bet.lifeAmount.toNumber()).to.equal(20 * 10 ** 9);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.lifePool.toNumber()).to.equal(20 * 10 ** 9);
+      expect(event.totalBettors.toNumber()).to.equal(1);
+
+      const stats = await program.account.userStats.fetch(userStatsPDA);
+      expect(stats.totalBets.toNumber()).to.equal(3);
     });
 
     it("fails with zero amount", async () => {
@@ -497,7 +730,7 @@ describe("prediction-market", () => {
 
       try {
         await program.methods
-          .placeBet({ doom: {} }, new anchor.BN(0))
+          .placeBet({ doom: {} }, new anchor.BN(0), null)
           .accounts({
             platformConfig: platformConfigPDA,
             event: eventPDA,
@@ -510,6 +743,10 @@ describe("prediction-market", () => {
             user: user2.publicKey,
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
+            eventOutcomes: null,
+            outcomeVault: null,
+            shareMint: null,
+            userShareAccount: null,
           })
           .signers([user2])
           .rpc();
@@ -520,52 +757,73 @@ describe("prediction-market", () => {
     });
   });
 
-  describe("resolve_event", () => {
-    // Tests for resolving events would go here
-    // Requires mocking time or using events with past deadlines
-  });
-
-  describe("claim_winnings", () => {
-    // Tests for claiming winnings would go here
-    // Requires resolved events with winning bets
-  });
-
-  describe("cancel_event", () => {
-    const eventId = new anchor.BN(2);
+  describe("resolve_event / claim_winnings", () => {
+    const eventId = new anchor.BN(10);
     let eventPDA: PublicKey;
     let doomVaultPDA: PublicKey;
     let lifeVaultPDA: PublicKey;
+    let user1BetPDA: PublicKey;
+    let user2BetPDA: PublicKey;
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    const claimAccounts = (user: Keypair, userBet: PublicKey, userDoom: PublicKey, userLife: PublicKey) => ({
+      platformConfig: platformConfigPDA,
+      event: eventPDA,
+      userBet,
+      userDoomAccount: userDoom,
+      userLifeAccount: userLife,
+      doomVault: doomVaultPDA,
+      lifeVault: lifeVaultPDA,
+      doomTreasury: doomTreasuryPDA,
+      lifeTreasury: lifeTreasuryPDA,
+      userStats: findUserStats(user.publicKey),
+      user: user.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      eventOutcomes: null,
+    });
 
     before(async () => {
       [eventPDA] = PublicKey.findProgramAddressSync(
         [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
         program.programId
       );
-
       [doomVaultPDA] = PublicKey.findProgramAddressSync(
         [Buffer.from("vault_doom"), eventPDA.toBuffer()],
         program.programId
       );
-
       [lifeVaultPDA] = PublicKey.findProgramAddressSync(
         [Buffer.from("vault_life"), eventPDA.toBuffer()],
         program.programId
       );
-
-      // Create event first
-      const [userStatsPDA] = PublicKey.findProgramAddressSync(
-        [Buffer.from("user_stats"), user1.publicKey.toBuffer()],
+      [user1BetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user1.publicKey.toBuffer()],
         program.programId
       );
+      [user2BetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
 
+      await mintTo(provider.connection, authority, lifeMint, user2Life, authority, 1000 * 10 ** 9);
+
+      // Short-lived event so it can be resolved within the test run
       const now = Math.floor(Date.now() / 1000);
       await program.methods
         .createEvent(
           eventId,
-          "Event to Cancel",
-          "This event will be cancelled",
-          new anchor.BN(now + 86400),
-          new anchor.BN(now + 172800)
+          "Short event",
+          "Resolves a few seconds after creation",
+          new anchor.BN(now + 3),
+          new anchor.BN(now + 3600),
+          null
         )
         .accounts({
           platformConfig: platformConfigPDA,
@@ -574,57 +832,4289 @@ describe("prediction-market", () => {
           lifeMint: lifeMint,
           doomVault: doomVaultPDA,
           lifeVault: lifeVaultPDA,
-          userStats: userStatsPDA,
+          userStats: findUserStats(user1.publicKey),
           creator: user1.publicKey,
           systemProgram: SystemProgram.programId,
           tokenProgram: TOKEN_PROGRAM_ID,
           rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
         })
         .signers([user1])
         .rpc();
+
+      const bet = async (user: Keypair, outcome: object, amount: number, userBet: PublicKey, userDoom: PublicKey, userLife: PublicKey) =>
+        program.methods
+          .placeBet(outcome as any, new anchor.BN(amount), null)
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            userBet,
+            userDoomAccount: userDoom,
+            userLifeAccount: userLife,
+            doomVault: doomVaultPDA,
+            lifeVault: lifeVaultPDA,
+            userStats: findUserStats(user.publicKey),
+            user: user.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            eventOutcomes: null,
+            outcomeVault: null,
+            shareMint: null,
+            userShareAccount: null,
+          })
+          .signers([user])
+          .rpc();
+
+      await bet(user1, { doom: {} }, 100 * 10 ** 9, user1BetPDA, user1Doom, user1Life);
+      await bet(user2, { life: {} }, 50 * 10 ** 9, user2BetPDA, user2Doom, user2Life);
+
+      await sleep(4000);
     });
 
-    it("cancels an event", async () => {
+    it("fails when not the oracle", async () => {
+      try {
+        await program.methods
+          .resolveEvent({ doom: {} })
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            proposerDoomAccount: oracleDoom,
+            bondVault: bondVaultPDA,
+            oracle: user1.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedOracle");
+      }
+    });
+
+    it("closes betting after the deadline", async () => {
       await program.methods
-        .cancelEvent()
+        .closeBetting()
+        .accounts({ event: eventPDA })
+        .rpc();
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ closed: {} });
+    });
+
+    it("resolves the event", async () => {
+      await program.methods
+        .resolveEvent({ doom: {} })
         .accounts({
           platformConfig: platformConfigPDA,
           event: eventPDA,
-          authority: authority.publicKey,
+          proposerDoomAccount: oracleDoom,
+          bondVault: bondVaultPDA,
+          oracle: oracle.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
         })
-        .signers([authority])
+        .signers([oracle])
         .rpc();
 
       const event = await program.account.predictionEvent.fetch(eventPDA);
-      expect(event.status).to.deep.equal({ cancelled: {} });
+      expect(event.status).to.deep.equal({ resolved: {} });
+      expect(event.outcome).to.deep.equal({ doom: {} });
     });
 
-    it("fails when not authority", async () => {
-      const newEventId = new anchor.BN(3);
-      const [newEventPDA] = PublicKey.findProgramAddressSync(
-        [Buffer.from("event"), newEventId.toArrayLike(Buffer, "le", 8)],
-        program.programId
-      );
-
+    it("fails to cancel a resolved event", async () => {
       try {
         await program.methods
           .cancelEvent()
           .accounts({
             platformConfig: platformConfigPDA,
-            event: newEventPDA,
-            authority: user1.publicKey,
+            event: eventPDA,
+            authority: authority.publicKey,
           })
+          .signers([authority])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventAlreadyResolved");
+      }
+    });
+
+    it("pays the winner stake plus share of the losing pool minus fee", async () => {
+      const doomBefore = Number((await getAccount(provider.connection, user1Doom)).amount);
+      const lifeBefore = Number((await getAccount(provider.connection, user1Life)).amount);
+      const configBefore = await program.account.platformConfig.fetch(platformConfigPDA);
+
+      await program.methods
+        .claimWinnings()
+        .accounts(claimAccounts(user1, user1BetPDA, user1Doom, user1Life))
+        .signers([user1])
+        .rpc();
+
+      // Sole DOOM bettor takes the whole 50 LIFE losing pool, less 2%
+      const share = 50 * 10 ** 9;
+      const fee = (share * FEE_BASIS_POINTS) / 10000;
+      const doomAfter = Number((await getAccount(provider.connection, user1Doom)).amount);
+      const lifeAfter = Number((await getAccount(provider.connection, user1Life)).amount);
+      expect(doomAfter - doomBefore).to.equal(100 * 10 ** 9);
+      expect(lifeAfter - lifeBefore).to.equal(share - fee);
+
+      const bet = await program.account.userBet.fetch(user1BetPDA);
+      expect(bet.claimed).to.equal(true);
+
+      const config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.totalLifeFees.sub(configBefore.totalLifeFees).toNumber()).to.equal(fee);
+
+      // The only winning stake has been paid out
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ settled: {} });
+    });
+
+    it("fails to claim twice", async () => {
+      try {
+        await program.methods
+          .claimWinnings()
+          .accounts(claimAccounts(user1, user1BetPDA, user1Doom, user1Life))
           .signers([user1])
           .rpc();
         expect.fail("Should have thrown an error");
       } catch (error) {
-        expect(error.message).to.include("Unauthorized");
+        expect(error.message).to.include("AlreadyClaimed");
       }
     });
-  });
 
-  describe("claim_refund", () => {
-    // Tests for claiming refunds from cancelled events
+    it("fails for a losing bet", async () => {
+      try {
+        await program.methods
+          .claimWinnings()
+          .accounts(claimAccounts(user2, user2BetPDA, user2Doom, user2Life))
+          .signers([user2])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NotAWinner");
+      }
+    });
+
+    it("records the win in the winner's stats", async () => {
+      const stats = await program.account.userStats.fetch(findUserStats(user1.publicKey));
+      const winnings = 50 * 10 ** 9 - (50 * 10 ** 9 * FEE_BASIS_POINTS) / 10000;
+      expect(stats.wins.toNumber()).to.equal(1);
+      expect(stats.losses.toNumber()).to.equal(0);
+      expect(stats.totalWon.toNumber()).to.equal(winnings);
+      expect(stats.currentStreak.toNumber()).to.equal(1);
+      expect(stats.bestStreak.toNumber()).to.equal(1);
+    });
+
+    it("fails to settle a losing bet while claims are paused", async () => {
+      const setPaused = (flags: number) =>
+        program.methods
+          .updatePlatform(null, null, flags, null, null, null)
+          .accounts({
+            platformConfig: platformConfigPDA,
+            authority: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc();
+
+      await setPaused(PAUSE_CLAIMS);
+      try {
+        await program.methods
+          .settleLosingBet()
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            userBet: user2BetPDA,
+            userStats: findUserStats(user2.publicKey),
+          })
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PlatformPaused");
+      }
+      await setPaused(0);
+    });
+
+    it("lets anyone settle a losing bet into the loser's stats", async () => {
+      await program.methods
+        .settleLosingBet()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: user2BetPDA,
+          userStats: findUserStats(user2.publicKey),
+        })
+        .rpc();
+
+      const stats = await program.account.userStats.fetch(findUserStats(user2.publicKey));
+      expect(stats.losses.toNumber()).to.equal(1);
+      expect(stats.totalLost.toNumber()).to.equal(50 * 10 ** 9);
+      expect(stats.netProfit.toNumber()).to.equal(-50 * 10 ** 9);
+      expect(stats.currentStreak.toNumber()).to.equal(-1);
+      expect(stats.worstStreak.toNumber()).to.equal(-1);
+
+      const bet = await program.account.userBet.fetch(user2BetPDA);
+      expect(bet.settledLoss).to.equal(true);
+      expect(bet.claimed).to.equal(false);
+
+      try {
+        await program.methods
+          .settleLosingBet()
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            userBet: user2BetPDA,
+            userStats: findUserStats(user2.publicKey),
+          })
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("LossAlreadySettled");
+      }
+    });
+
+    it("fails to settle a winning bet as a loss", async () => {
+      try {
+        await program.methods
+          .settleLosingBet()
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            userBet: user1BetPDA,
+            userStats: findUserStats(user1.publicKey),
+          })
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        // Already claimed as a win
+        expect(error.message).to.include("AlreadyClaimed");
+      }
+    });
+  });
+
+  describe("categorical events", () => {
+    const eventId = new anchor.BN(40);
+    const names = ["Asteroid", "Pandemic", "AI"];
+    let eventPDA: PublicKey;
+    let outcomesPDA: PublicKey;
+    let doomVaultPDA: PublicKey;
+    let lifeVaultPDA: PublicKey;
+    let outcomeVaults: PublicKey[];
+    let user1BetPDA: PublicKey;
+    let user2BetPDA: PublicKey;
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    const bet = async (user: Keypair, index: number, amount: number, userBet: PublicKey, userDoom: PublicKey, userLife: PublicKey) =>
+      program.methods
+        .placeBet({ categorical: { 0: index } } as any, new anchor.BN(amount), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet,
+          userDoomAccount: userDoom,
+          userLifeAccount: userLife,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          userStats: findUserStats(user.publicKey),
+          user: user.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: outcomesPDA,
+          outcomeVault: outcomeVaults[index],
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user])
+        .rpc();
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      [outcomesPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("outcomes"), eventPDA.toBuffer()],
+        program.programId
+      );
+      [doomVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_doom"), eventPDA.toBuffer()],
+        program.programId
+      );
+      [lifeVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_life"), eventPDA.toBuffer()],
+        program.programId
+      );
+      outcomeVaults = names.map(
+        (_, index) =>
+          PublicKey.findProgramAddressSync(
+            [Buffer.from("vault_outcome"), eventPDA.toBuffer(), Buffer.from([index])],
+            program.programId
+          )[0]
+      );
+      [user1BetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user1.publicKey.toBuffer()],
+        program.programId
+      );
+      [user2BetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      await mintTo(provider.connection, authority, doomMint, user2Doom, authority, 1000 * 10 ** 9);
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "What ends us?",
+          "Categorical event with three outcomes",
+          new anchor.BN(now + 8),
+          new anchor.BN(now + 3600),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+    });
+
+    it("fails with a single outcome", async () => {
+      try {
+        await program.methods
+          .addEventOutcomes(["Asteroid"])
+          .accounts({
+            event: eventPDA,
+            eventOutcomes: outcomesPDA,
+            creator: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidOutcomeCount");
+      }
+    });
+
+    it("adds named outcomes and their vaults", async () => {
+      await program.methods
+        .addEventOutcomes(names)
+        .accounts({
+          event: eventPDA,
+          eventOutcomes: outcomesPDA,
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user1])
+        .rpc();
+
+      for (let index = 0; index < names.length; index++) {
+        await program.methods
+          .initializeOutcomeVault(index)
+          .accounts({
+            event: eventPDA,
+            eventOutcomes: outcomesPDA,
+            doomVault: doomVaultPDA,
+            doomMint: doomMint,
+            outcomeVault: outcomeVaults[index],
+            payer: provider.wallet.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          })
+          .rpc();
+      }
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.kind).to.deep.equal({ categorical: {} });
+      expect(event.outcomeCount).to.equal(3);
+
+      const outcomes = await program.account.eventOutcomes.fetch(outcomesPDA);
+      expect(outcomes.names).to.deep.equal(names);
+      expect(outcomes.pools.map((pool) => pool.toNumber())).to.deep.equal([0, 0, 0]);
+    });
+
+    it("places bets on outcomes", async () => {
+      await bet(user1, 0, 60 * 10 ** 9, user1BetPDA, user1Doom, user1Life);
+      await bet(user2, 1, 30 * 10 ** 9, user2BetPDA, user2Doom, user2Life);
+      await bet(user2, 2, 10 * 10 ** 9, user2BetPDA, user2Doom, user2Life);
+
+      const outcomes = await program.account.eventOutcomes.fetch(outcomesPDA);
+      expect(outcomes.pools.map((pool) => pool.toNumber())).to.deep.equal([
+        60 * 10 ** 9,
+        30 * 10 ** 9,
+        10 * 10 ** 9,
+      ]);
+
+      const user2Bet = await program.account.userBet.fetch(user2BetPDA);
+      expect(user2Bet.outcomeAmounts.map((stake) => stake.toNumber())).to.deep.equal([
+        0,
+        30 * 10 ** 9,
+        10 * 10 ** 9,
+      ]);
+    });
+
+    it("rejects binary outcomes", async () => {
+      try {
+        await program.methods
+          .placeBet({ doom: {} }, new anchor.BN(10 * 10 ** 9), null)
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            userBet: user1BetPDA,
+            userDoomAccount: user1Doom,
+            userLifeAccount: user1Life,
+            doomVault: doomVaultPDA,
+            lifeVault: lifeVaultPDA,
+            userStats: findUserStats(user1.publicKey),
+            user: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            eventOutcomes: outcomesPDA,
+            outcomeVault: null,
+            shareMint: null,
+            userShareAccount: null,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidOutcome");
+      }
+    });
+
+    it("pays the winner from every losing outcome pool", async () => {
+      await sleep(8000);
+
+      await program.methods
+        .resolveEvent({ categorical: { 0: 0 } } as any)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          proposerDoomAccount: oracleDoom,
+          bondVault: bondVaultPDA,
+          oracle: oracle.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([oracle])
+        .rpc();
+
+      const before = Number((await getAccount(provider.connection, user1Doom)).amount);
+
+      await program.methods
+        .claimWinnings()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: user1BetPDA,
+          userDoomAccount: user1Doom,
+          userLifeAccount: user1Life,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          doomTreasury: doomTreasuryPDA,
+          lifeTreasury: lifeTreasuryPDA,
+          userStats: findUserStats(user1.publicKey),
+          user: user1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: outcomesPDA,
+        })
+        .remainingAccounts(
+          outcomeVaults.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false }))
+        )
+        .signers([user1])
+        .rpc();
+
+      // Sole winner takes both losing pools, less 2% of each
+      const share = 40 * 10 ** 9;
+      const fee = (share * FEE_BASIS_POINTS) / 10000;
+      const after = Number((await getAccount(provider.connection, user1Doom)).amount);
+      expect(after - before).to.equal(60 * 10 ** 9 + share - fee);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ settled: {} });
+    });
+  });
+
+  describe("scalar events", () => {
+    const eventId = new anchor.BN(50);
+    let eventPDA: PublicKey;
+    let doomVaultPDA: PublicKey;
+    let lifeVaultPDA: PublicKey;
+    let user1BetPDA: PublicKey;
+    let user2BetPDA: PublicKey;
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    const claim = (user: Keypair, userBet: PublicKey, userDoom: PublicKey, userLife: PublicKey) =>
+      program.methods
+        .claimWinnings()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet,
+          userDoomAccount: userDoom,
+          userLifeAccount: userLife,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          doomTreasury: doomTreasuryPDA,
+          lifeTreasury: lifeTreasuryPDA,
+          userStats: findUserStats(user.publicKey),
+          user: user.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+        })
+        .signers([user])
+        .rpc();
+
+    const balance = async (account: PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      [doomVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_doom"), eventPDA.toBuffer()],
+        program.programId
+      );
+      [lifeVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_life"), eventPDA.toBuffer()],
+        program.programId
+      );
+      [user1BetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user1.publicKey.toBuffer()],
+        program.programId
+      );
+      [user2BetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "Temperature anomaly",
+          "Global temperature anomaly in tenths of a degree",
+          new anchor.BN(now + 6),
+          new anchor.BN(now + 3600),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+    });
+
+    it("fails with an empty range", async () => {
+      try {
+        await program.methods
+          .setScalarRange(new anchor.BN(10), new anchor.BN(10))
+          .accounts({ event: eventPDA, creator: user1.publicKey })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidScalarRange");
+      }
+    });
+
+    it("sets the range and takes long and short bets", async () => {
+      await program.methods
+        .setScalarRange(new anchor.BN(0), new anchor.BN(100))
+        .accounts({ event: eventPDA, creator: user1.publicKey })
+        .signers([user1])
+        .rpc();
+
+      const bet = (user: Keypair, outcome: object, amount: number, userBet: PublicKey, userDoom: PublicKey, userLife: PublicKey) =>
+        program.methods
+          .placeBet(outcome as any, new anchor.BN(amount), null)
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            userBet,
+            userDoomAccount: userDoom,
+            userLifeAccount: userLife,
+            doomVault: doomVaultPDA,
+            lifeVault: lifeVaultPDA,
+            userStats: findUserStats(user.publicKey),
+            user: user.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            eventOutcomes: null,
+            outcomeVault: null,
+            shareMint: null,
+            userShareAccount: null,
+          })
+          .signers([user])
+          .rpc();
+
+      await bet(user1, { doom: {} }, 40 * 10 ** 9, user1BetPDA, user1Doom, user1Life);
+      await bet(user2, { life: {} }, 60 * 10 ** 9, user2BetPDA, user2Doom, user2Life);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.kind).to.deep.equal({ scalar: {} });
+      expect(event.lowerBound.toNumber()).to.equal(0);
+      expect(event.upperBound.toNumber()).to.equal(100);
+    });
+
+    it("rejects a binary resolution", async () => {
+      await sleep(6000);
+
+      try {
+        await program.methods
+          .resolveEvent({ doom: {} })
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            proposerDoomAccount: oracleDoom,
+            bondVault: bondVaultPDA,
+            oracle: oracle.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([oracle])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidOutcome");
+      }
+    });
+
+    it("interpolates payouts between long and short", async () => {
+      await program.methods
+        .resolveEvent({ scalar: { 0: new anchor.BN(75) } } as any)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          proposerDoomAccount: oracleDoom,
+          bondVault: bondVaultPDA,
+          oracle: oracle.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([oracle])
+        .rpc();
+
+      // Longs are owed 75% of both pools, shorts 25%
+      const longDoomBefore = await balance(user1Doom);
+      const longLifeBefore = await balance(user1Life);
+      await claim(user1, user1BetPDA, user1Doom, user1Life);
+      const longShare = 45 * 10 ** 9;
+      expect((await balance(user1Doom)) - longDoomBefore).to.equal(30 * 10 ** 9);
+      expect((await balance(user1Life)) - longLifeBefore).to.equal(
+        longShare - (longShare * FEE_BASIS_POINTS) / 10000
+      );
+
+      const shortDoomBefore = await balance(user2Doom);
+      const shortLifeBefore = await balance(user2Life);
+      await claim(user2, user2BetPDA, user2Doom, user2Life);
+      const shortShare = 10 * 10 ** 9;
+      expect((await balance(user2Life)) - shortLifeBefore).to.equal(15 * 10 ** 9);
+      expect((await balance(user2Doom)) - shortDoomBefore).to.equal(
+        shortShare - (shortShare * FEE_BASIS_POINTS) / 10000
+      );
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ settled: {} });
+    });
+  });
+
+  describe("dispute resolution", () => {
+    const BOND = 10 * 10 ** 9;
+    const disputedId = new anchor.BN(60);
+    const undisputedId = new anchor.BN(61);
+    let disputedPDA: PublicKey;
+    let undisputedPDA: PublicKey;
+    let user1Doom: PublicKey;
+    let user2Doom: PublicKey;
+
+    const findEvent = (eventId: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+
+    const balance = async (account: PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    const propose = (event: PublicKey) =>
+      program.methods
+        .resolveEvent({ doom: {} })
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event,
+          proposerDoomAccount: oracleDoom,
+          bondVault: bondVaultPDA,
+          oracle: oracle.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([oracle])
+        .rpc();
+
+    const dispute = (event: PublicKey, user: Keypair, userDoom: PublicKey) =>
+      program.methods
+        .disputeResolution()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event,
+          disputerDoomAccount: userDoom,
+          bondVault: bondVaultPDA,
+          disputer: user.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+
+    const finalize = (event: PublicKey) =>
+      program.methods
+        .finalizeResolution()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event,
+          proposerDoomAccount: oracleDoom,
+          bondVault: bondVaultPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    before(async () => {
+      disputedPDA = findEvent(disputedId);
+      undisputedPDA = findEvent(undisputedId);
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+
+      await mintTo(provider.connection, authority, doomMint, oracleDoom, authority, 100 * 10 ** 9);
+
+      await program.methods
+        .updatePlatform(null, null, null, new anchor.BN(4), null, null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const now = Math.floor(Date.now() / 1000);
+      for (const [eventId, event] of [
+        [disputedId, disputedPDA],
+        [undisputedId, undisputedPDA],
+      ] as [anchor.BN, PublicKey][]) {
+        const [doomVault] = PublicKey.findProgramAddressSync(
+          [Buffer.from("vault_doom"), event.toBuffer()],
+          program.programId
+        );
+        const [lifeVault] = PublicKey.findProgramAddressSync(
+          [Buffer.from("vault_life"), event.toBuffer()],
+          program.programId
+        );
+        await program.methods
+          .createEvent(
+            eventId,
+            "Disputable event",
+            "Resolved through an optimistic proposal",
+            new anchor.BN(now + 3),
+            new anchor.BN(now + 3600),
+            null
+          )
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event,
+            doomMint: doomMint,
+            lifeMint: lifeMint,
+            doomVault,
+            lifeVault,
+            userStats: findUserStats(user1.publicKey),
+            creator: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            resolverAllowlist: null,
+            creatorDoomAccount: null,
+            bondVault: null,
+          })
+          .signers([user1])
+          .rpc();
+      }
+
+      await sleep(3500);
+    });
+
+    after(async () => {
+      await program.methods
+        .updatePlatform(null, null, null, new anchor.BN(0), null, null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+    });
+
+    it("proposes an outcome against the oracle's bond", async () => {
+      const oracleBefore = await balance(oracleDoom);
+
+      await propose(disputedPDA);
+      await propose(undisputedPDA);
+
+      const event = await program.account.predictionEvent.fetch(disputedPDA);
+      expect(event.status).to.deep.equal({ proposed: {} });
+      expect(event.outcome).to.deep.equal({ doom: {} });
+      expect(event.proposer.toString()).to.equal(oracle.publicKey.toString());
+      expect(event.bond.toNumber()).to.equal(BOND);
+      expect(oracleBefore - (await balance(oracleDoom))).to.equal(2 * BOND);
+    });
+
+    it("fails to finalize during the dispute window", async () => {
+      try {
+        await finalize(undisputedPDA);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("DisputeWindowOpen");
+      }
+    });
+
+    it("lets anyone dispute by matching the bond", async () => {
+      const before = await balance(user2Doom);
+
+      await dispute(disputedPDA, user2, user2Doom);
+
+      const event = await program.account.predictionEvent.fetch(disputedPDA);
+      expect(event.status).to.deep.equal({ disputed: {} });
+      expect(event.disputer.toString()).to.equal(user2.publicKey.toString());
+      expect(before - (await balance(user2Doom))).to.equal(BOND);
+    });
+
+    it("fails to dispute twice", async () => {
+      try {
+        await dispute(disputedPDA, user1, user1Doom);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NoResolutionProposed");
+      }
+    });
+
+    it("finalizes an undisputed proposal and returns the bond", async () => {
+      await sleep(4000);
+      const before = await balance(oracleDoom);
+
+      await finalize(undisputedPDA);
+
+      const event = await program.account.predictionEvent.fetch(undisputedPDA);
+      expect(event.status).to.deep.equal({ resolved: {} });
+      expect((await balance(oracleDoom)) - before).to.equal(BOND);
+    });
+
+    it("fails to settle a dispute when not authority", async () => {
+      try {
+        await program.methods
+          .settleDispute({ life: {} })
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: disputedPDA,
+            winnerDoomAccount: user2Doom,
+            bondVault: bondVaultPDA,
+            authority: user2.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([user2])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+
+    it("overturns the proposal and slashes the proposer's bond to the disputer", async () => {
+      const before = await balance(user2Doom);
+
+      await program.methods
+        .settleDispute({ life: {} })
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: disputedPDA,
+          winnerDoomAccount: user2Doom,
+          bondVault: bondVaultPDA,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+
+      const event = await program.account.predictionEvent.fetch(disputedPDA);
+      expect(event.status).to.deep.equal({ resolved: {} });
+      expect(event.outcome).to.deep.equal({ life: {} });
+      expect((await balance(user2Doom)) - before).to.equal(2 * BOND);
+    });
+
+    it("expires a dispute nobody settles and refunds both bonds", async () => {
+      const stuckPDA = findEvent(new anchor.BN(62));
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(new anchor.BN(62), "Stuck dispute", "The moderator never rules", new anchor.BN(now + 2), new anchor.BN(now + 6), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: stuckPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: PublicKey.findProgramAddressSync([Buffer.from("vault_doom"), stuckPDA.toBuffer()], program.programId)[0],
+          lifeVault: PublicKey.findProgramAddressSync([Buffer.from("vault_life"), stuckPDA.toBuffer()], program.programId)[0],
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+      await sleep(2500);
+      await propose(stuckPDA);
+      await dispute(stuckPDA, user2, user2Doom);
+
+      const expire = (withBonds: boolean) =>
+        program.methods
+          .expireEvent()
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: stuckPDA,
+            bondVault: withBonds ? bondVaultPDA : null,
+            proposerDoomAccount: withBonds ? oracleDoom : null,
+            disputerDoomAccount: withBonds ? user2Doom : null,
+            tokenProgram: withBonds ? TOKEN_PROGRAM_ID : null,
+          })
+          .rpc();
+
+      // Disputes get a dispute window past the resolution deadline
+      await sleep(4000);
+      try {
+        await expire(true);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("ResolutionDeadlineNotPassed");
+      }
+
+      await sleep(4500);
+      try {
+        await expire(false);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("DisputeBondAccountsRequired");
+      }
+
+      const oracleBefore = await balance(oracleDoom);
+      const disputerBefore = await balance(user2Doom);
+      await expire(true);
+
+      const event = await program.account.predictionEvent.fetch(stuckPDA);
+      expect(event.status).to.deep.equal({ expired: {} });
+      expect((await balance(oracleDoom)) - oracleBefore).to.equal(BOND);
+      expect((await balance(user2Doom)) - disputerBefore).to.equal(BOND);
+    });
+  });
+
+  describe("oracle committee", () => {
+    const agreedId = new anchor.BN(70);
+    const splitId = new anchor.BN(71);
+    let agreedPDA: PublicKey;
+    let splitPDA: PublicKey;
+    let committeePDA: PublicKey;
+    let members: Keypair[];
+
+    const findEvent = (eventId: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+
+    const vote = (event: PublicKey, member: Keypair, outcome: object) =>
+      program.methods
+        .submitOracleVote(outcome as any)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          oracleCommittee: committeePDA,
+          event,
+          oracleVote: PublicKey.findProgramAddressSync(
+            [Buffer.from("oracle_vote"), event.toBuffer(), member.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          member: member.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([member])
+        .rpc();
+
+    before(async () => {
+      agreedPDA = findEvent(agreedId);
+      splitPDA = findEvent(splitId);
+      [committeePDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("oracle_committee")],
+        program.programId
+      );
+
+      members = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+      for (const member of members) {
+        const sig = await provider.connection.requestAirdrop(member.publicKey, LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      }
+
+      const now = Math.floor(Date.now() / 1000);
+      for (const [eventId, event] of [
+        [agreedId, agreedPDA],
+        [splitId, splitPDA],
+      ] as [anchor.BN, PublicKey][]) {
+        await program.methods
+          .createEvent(
+            eventId,
+            "Committee event",
+            "Resolved by oracle committee vote",
+            new anchor.BN(now + 3),
+            new anchor.BN(now + 3600),
+            null
+          )
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event,
+            doomMint: doomMint,
+            lifeMint: lifeMint,
+            doomVault: PublicKey.findProgramAddressSync(
+              [Buffer.from("vault_doom"), event.toBuffer()],
+              program.programId
+            )[0],
+            lifeVault: PublicKey.findProgramAddressSync(
+              [Buffer.from("vault_life"), event.toBuffer()],
+              program.programId
+            )[0],
+            userStats: findUserStats(user1.publicKey),
+            creator: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            resolverAllowlist: null,
+            creatorDoomAccount: null,
+            bondVault: null,
+          })
+          .signers([user1])
+          .rpc();
+      }
+    });
+
+    it("fails with a threshold above the member count", async () => {
+      try {
+        await program.methods
+          .setOracleCommittee(members.map((member) => member.publicKey), 4)
+          .accounts({
+            platformConfig: platformConfigPDA,
+            oracleCommittee: committeePDA,
+            authority: authority.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([authority])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidCommittee");
+      }
+    });
+
+    it("sets a 2-of-3 committee", async () => {
+      await program.methods
+        .setOracleCommittee(members.map((member) => member.publicKey), 2)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          oracleCommittee: committeePDA,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      const committee = await program.account.oracleCommittee.fetch(committeePDA);
+      expect(committee.members.length).to.equal(3);
+      expect(committee.threshold).to.equal(2);
+    });
+
+    it("rejects votes from non-members", async () => {
+      await sleep(3000);
+
+      try {
+        await vote(agreedPDA, user1, { doom: {} });
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NotCommitteeMember");
+      }
+    });
+
+    it("resolves once the threshold agrees", async () => {
+      await vote(agreedPDA, members[0], { doom: {} });
+
+      let event = await program.account.predictionEvent.fetch(agreedPDA);
+      expect(event.status).to.deep.equal({ active: {} });
+      expect(event.committeeVotes).to.equal(1);
+
+      await vote(agreedPDA, members[1], { doom: {} });
+
+      event = await program.account.predictionEvent.fetch(agreedPDA);
+      expect(event.status).to.deep.equal({ resolved: {} });
+      expect(event.outcome).to.deep.equal({ doom: {} });
+    });
+
+    it("marks the event disputed on conflicting votes", async () => {
+      await vote(splitPDA, members[0], { doom: {} });
+      await vote(splitPDA, members[1], { life: {} });
+
+      const event = await program.account.predictionEvent.fetch(splitPDA);
+      expect(event.status).to.deep.equal({ disputed: {} });
+      expect(event.outcome).to.equal(null);
+
+      try {
+        await vote(splitPDA, members[2], { doom: {} });
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventNotOpenForVotes");
+      }
+    });
+
+    it("closes oracle votes once the event is resolved", async () => {
+      const member = members[0];
+      const oracleVote = PublicKey.findProgramAddressSync(
+        [Buffer.from("oracle_vote"), agreedPDA.toBuffer(), member.publicKey.toBuffer()],
+        program.programId
+      )[0];
+
+      const memberBefore = await provider.connection.getBalance(member.publicKey);
+      await program.methods
+        .closeOracleVote()
+        .accounts({
+          event: agreedPDA,
+          oracleVote,
+          member: member.publicKey,
+          creator: user1.publicKey,
+        })
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(oracleVote)).to.be.null;
+      expect(await provider.connection.getBalance(member.publicKey)).to.be.greaterThan(memberBefore);
+      const event = await program.account.predictionEvent.fetch(agreedPDA);
+      expect(event.oracleVotes).to.equal(1);
+    });
+  });
+
+  describe("event resolver override", () => {
+    const eventId = new anchor.BN(80);
+    let eventPDA: PublicKey;
+    let allowlistPDA: PublicKey;
+    let resolver: Keypair;
+    let resolverDoom: PublicKey;
+
+    const createEvent = (deadline: number, allowlist: PublicKey | null) =>
+      program.methods
+        .createEvent(
+          eventId,
+          "Climate data event",
+          "Resolved by a climate data oracle",
+          new anchor.BN(deadline),
+          new anchor.BN(deadline + 3600),
+          resolver.publicKey
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: PublicKey.findProgramAddressSync(
+            [Buffer.from("vault_doom"), eventPDA.toBuffer()],
+            program.programId
+          )[0],
+          lifeVault: PublicKey.findProgramAddressSync(
+            [Buffer.from("vault_life"), eventPDA.toBuffer()],
+            program.programId
+          )[0],
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: allowlist,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+
+    const resolve = (signer: Keypair, signerDoom: PublicKey) =>
+      program.methods
+        .resolveEvent({ life: {} })
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          proposerDoomAccount: signerDoom,
+          bondVault: bondVaultPDA,
+          oracle: signer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc();
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      [allowlistPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("resolver_allowlist")],
+        program.programId
+      );
+
+      resolver = Keypair.generate();
+      const sig = await provider.connection.requestAirdrop(resolver.publicKey, LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+      resolverDoom = (await getOrCreateAssociatedTokenAccount(provider.connection, resolver, doomMint, resolver.publicKey)).address;
+    });
+
+    it("fails to bind a resolver that is not allowlisted", async () => {
+      try {
+        await createEvent(Math.floor(Date.now() / 1000) + 3, null);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("ResolverNotAllowed");
+      }
+    });
+
+    it("binds an allowlisted resolver at creation", async () => {
+      await program.methods
+        .updateResolverAllowlist(resolver.publicKey, true)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          resolverAllowlist: allowlistPDA,
+          creatorDoomAccount: null,
+          bondVault: null,
+          authority: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+      await createEvent(Math.floor(Date.now() / 1000) + 3, allowlistPDA);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.resolver.toString()).to.equal(resolver.publicKey.toString());
+    });
+
+    it("rejects resolution by the platform oracle", async () => {
+      await sleep(3500);
+
+      try {
+        await resolve(oracle, oracleDoom);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedOracle");
+      }
+    });
+
+    it("rejects oracle committee votes", async () => {
+      try {
+        await program.methods
+          .submitOracleVote({ doom: {} })
+          .accounts({
+            platformConfig: platformConfigPDA,
+            oracleCommittee: PublicKey.findProgramAddressSync(
+              [Buffer.from("oracle_committee")],
+              program.programId
+            )[0],
+            event: eventPDA,
+            oracleVote: PublicKey.findProgramAddressSync(
+              [Buffer.from("oracle_vote"), eventPDA.toBuffer(), resolver.publicKey.toBuffer()],
+              program.programId
+            )[0],
+            member: resolver.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([resolver])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("UnauthorizedOracle");
+      }
+    });
+
+    it("lets the bound resolver resolve the event", async () => {
+      await resolve(resolver, resolverDoom);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ resolved: {} });
+      expect(event.outcome).to.deep.equal({ life: {} });
+    });
+  });
+
+  describe("resolve_from_price_feed", () => {
+    // Mock Pyth program and price accounts loaded by the test validator, see Anchor.toml
+    const PYTH_PROGRAM = new PublicKey("gSbePebfvPy7tRqimPoVecS2UsBvYv46ynrzWocc92s");
+    const SOL_USD_FEED = new PublicKey("5TmxnJG1itqjfy1HgGnDfwf16jR7pduWztEV6zSbZ2V4"); // $150.00
+    const WIDE_CONFIDENCE_FEED = new PublicKey("Gb7Btsi7rwDPMym4gJ9za4uvwX8JpTyKBR1J51hjbUv");
+    const STALE_FEED = new PublicKey("GM6eEB7SYsfEF4EuES4bP3a5JaKHmCPYZMwPssYjixQ8");
+    const PUBLISH_TIME_OFFSET = 96;
+
+    const solId = new anchor.BN(90);
+    const wideId = new anchor.BN(91);
+    const staleId = new anchor.BN(92);
+    let solPDA: PublicKey;
+    let widePDA: PublicKey;
+    let stalePDA: PublicKey;
+
+    const findEvent = (eventId: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+
+    const resolve = (event: PublicKey, priceFeed: PublicKey) =>
+      program.methods
+        .resolveFromPriceFeed()
+        .accounts({ platformConfig: platformConfigPDA, event, priceFeed })
+        .rpc();
+
+    // Publish the fixture's price again at `publishTime`, via the mock Pyth program
+    const publishPrice = async (priceFeed: PublicKey, publishTime: number) => {
+      const data = Buffer.alloc(8 + 4 + 4 + 8);
+      createHash("sha256").update("global:write_price").digest().copy(data, 0, 0, 8);
+      data.writeUInt32LE(PUBLISH_TIME_OFFSET, 8);
+      data.writeUInt32LE(8, 12);
+      data.writeBigInt64LE(BigInt(publishTime), 16);
+      await provider.sendAndConfirm(
+        new Transaction().add(
+          new TransactionInstruction({
+            programId: PYTH_PROGRAM,
+            keys: [{ pubkey: priceFeed, isSigner: false, isWritable: true }],
+            data,
+          })
+        )
+      );
+    };
+
+    const clusterTime = async () =>
+      provider.connection.getBlockTime(await provider.connection.getSlot());
+
+    before(async () => {
+      solPDA = findEvent(solId);
+      widePDA = findEvent(wideId);
+      stalePDA = findEvent(staleId);
+
+      const now = Math.floor(Date.now() / 1000);
+      for (const [eventId, event, priceFeed] of [
+        [solId, solPDA, SOL_USD_FEED],
+        [wideId, widePDA, WIDE_CONFIDENCE_FEED],
+        [staleId, stalePDA, STALE_FEED],
+      ] as [anchor.BN, PublicKey, PublicKey][]) {
+        await program.methods
+          .createEvent(
+            eventId,
+            "SOL below $200",
+            "Resolves from the SOL/USD price account",
+            new anchor.BN(now + 3),
+            new anchor.BN(now + 3600),
+            null
+          )
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event,
+            doomMint: doomMint,
+            lifeMint: lifeMint,
+            doomVault: PublicKey.findProgramAddressSync(
+              [Buffer.from("vault_doom"), event.toBuffer()],
+              program.programId
+            )[0],
+            lifeVault: PublicKey.findProgramAddressSync(
+              [Buffer.from("vault_life"), event.toBuffer()],
+              program.programId
+            )[0],
+            userStats: findUserStats(user1.publicKey),
+            creator: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            resolverAllowlist: null,
+            creatorDoomAccount: null,
+            bondVault: null,
+          })
+          .signers([user1])
+          .rpc();
+
+        await program.methods
+          .setPriceTrigger(new anchor.BN(20000), { below: {} })
+          .accounts({ event, priceFeed, creator: user1.publicKey })
+          .signers([user1])
+          .rpc();
+      }
+    });
+
+    it("stores the trigger on the event", async () => {
+      const event = await program.account.predictionEvent.fetch(solPDA);
+      expect(event.priceFeed.toString()).to.equal(SOL_USD_FEED.toString());
+      expect(event.priceThreshold.toNumber()).to.equal(20000);
+      expect(event.priceExpo).to.equal(-2);
+      expect(event.priceComparator).to.deep.equal({ below: {} });
+    });
+
+    it("fails to turn a price-triggered event categorical", async () => {
+      try {
+        await program.methods
+          .addEventOutcomes(["Asteroid", "Pandemic"])
+          .accounts({
+            event: solPDA,
+            eventOutcomes: PublicKey.findProgramAddressSync(
+              [Buffer.from("outcomes"), solPDA.toBuffer()],
+              program.programId
+            )[0],
+            creator: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PriceTriggered");
+      }
+    });
+
+    it("fails before the deadline", async () => {
+      try {
+        await resolve(solPDA, SOL_USD_FEED);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventNotResolved");
+      }
+    });
+
+    it("fails with a different price account", async () => {
+      await sleep(3500);
+
+      try {
+        await resolve(solPDA, WIDE_CONFIDENCE_FEED);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PriceFeedMismatch");
+      }
+    });
+
+    it("rejects oracle resolution of a price-triggered event", async () => {
+      try {
+        await program.methods
+          .resolveEvent({ life: {} })
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: stalePDA,
+            proposerDoomAccount: oracleDoom,
+            bondVault: bondVaultPDA,
+            oracle: oracle.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([oracle])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PriceTriggered");
+      }
+    });
+
+    it("fails with a stale price", async () => {
+      try {
+        await resolve(stalePDA, STALE_FEED);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("StalePrice");
+      }
+    });
+
+    it("fails with a price published in the future", async () => {
+      await publishPrice(SOL_USD_FEED, (await clusterTime()) + 3600);
+
+      try {
+        await resolve(solPDA, SOL_USD_FEED);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PriceInFuture");
+      }
+    });
+
+    it("fails when the confidence interval is too wide", async () => {
+      await publishPrice(WIDE_CONFIDENCE_FEED, await clusterTime());
+
+      try {
+        await resolve(widePDA, WIDE_CONFIDENCE_FEED);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PriceConfidenceTooWide");
+      }
+    });
+
+    it("resolves permissionlessly from the price", async () => {
+      await publishPrice(SOL_USD_FEED, await clusterTime());
+      await resolve(solPDA, SOL_USD_FEED);
+
+      const event = await program.account.predictionEvent.fetch(solPDA);
+      expect(event.status).to.deep.equal({ resolved: {} });
+      expect(event.outcome).to.deep.equal({ doom: {} });
+    });
+  });
+
+  describe("invalid and draw outcomes", () => {
+    const invalidId = new anchor.BN(100);
+    const drawId = new anchor.BN(101);
+    let invalidPDA: PublicKey;
+    let drawPDA: PublicKey;
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    const findEvent = (eventId: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    const findVault = (seed: string, event: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed), event.toBuffer()], program.programId)[0];
+    const findUserBet = (event: PublicKey, user: Keypair) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), event.toBuffer(), user.publicKey.toBuffer()],
+        program.programId
+      )[0];
+
+    const balance = async (account: PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    const resolve = (event: PublicKey, outcome: object) =>
+      program.methods
+        .resolveEvent(outcome as any)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event,
+          proposerDoomAccount: oracleDoom,
+          bondVault: bondVaultPDA,
+          oracle: oracle.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([oracle])
+        .rpc();
+
+    const claimAccounts = (event: PublicKey, user: Keypair, userDoom: PublicKey, userLife: PublicKey) => ({
+      platformConfig: platformConfigPDA,
+      event,
+      userBet: findUserBet(event, user),
+      userDoomAccount: userDoom,
+      userLifeAccount: userLife,
+      doomVault: findVault("vault_doom", event),
+      lifeVault: findVault("vault_life", event),
+      doomTreasury: doomTreasuryPDA,
+      lifeTreasury: lifeTreasuryPDA,
+      userStats: findUserStats(user.publicKey),
+      user: user.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      eventOutcomes: null,
+    });
+
+    before(async () => {
+      invalidPDA = findEvent(invalidId);
+      drawPDA = findEvent(drawId);
+
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      const now = Math.floor(Date.now() / 1000);
+      for (const [eventId, event] of [
+        [invalidId, invalidPDA],
+        [drawId, drawPDA],
+      ] as [anchor.BN, PublicKey][]) {
+        await program.methods
+          .createEvent(
+            eventId,
+            "Ambiguous event",
+            "Resolves invalid or as a draw",
+            new anchor.BN(now + 4),
+            new anchor.BN(now + 3600),
+            null
+          )
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event,
+            doomMint: doomMint,
+            lifeMint: lifeMint,
+            doomVault: findVault("vault_doom", event),
+            lifeVault: findVault("vault_life", event),
+            userStats: findUserStats(user1.publicKey),
+            creator: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            resolverAllowlist: null,
+            creatorDoomAccount: null,
+            bondVault: null,
+          })
+          .signers([user1])
+          .rpc();
+
+        for (const [user, outcome, amount, userDoom, userLife] of [
+          [user1, { doom: {} }, 10 * 10 ** 9, user1Doom, user1Life],
+          [user2, { life: {} }, 20 * 10 ** 9, user2Doom, user2Life],
+        ] as [Keypair, object, number, PublicKey, PublicKey][]) {
+          await program.methods
+            .placeBet(outcome as any, new anchor.BN(amount), null)
+            .accounts({
+              platformConfig: platformConfigPDA,
+              event,
+              userBet: findUserBet(event, user),
+              userDoomAccount: userDoom,
+              userLifeAccount: userLife,
+              doomVault: findVault("vault_doom", event),
+              lifeVault: findVault("vault_life", event),
+              userStats: findUserStats(user.publicKey),
+              user: user.publicKey,
+              systemProgram: SystemProgram.programId,
+              tokenProgram: TOKEN_PROGRAM_ID,
+              eventOutcomes: null,
+              outcomeVault: null,
+              shareMint: null,
+              userShareAccount: null,
+            })
+            .signers([user])
+            .rpc();
+        }
+      }
+
+      await sleep(4500);
+    });
+
+    it("refunds every bet in full on an invalid event", async () => {
+      await resolve(invalidPDA, { invalid: {} });
+
+      try {
+        await program.methods
+          .claimWinnings()
+          .accounts(claimAccounts(invalidPDA, user1, user1Doom, user1Life))
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventResolvedInvalid");
+      }
+
+      const before = await balance(user1Doom);
+      await program.methods
+        .claimRefund()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: invalidPDA,
+          userBet: findUserBet(invalidPDA, user1),
+          userDoomAccount: user1Doom,
+          userLifeAccount: user1Life,
+          doomVault: findVault("vault_doom", invalidPDA),
+          lifeVault: findVault("vault_life", invalidPDA),
+          user: user1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+        })
+        .signers([user1])
+        .rpc();
+
+      expect((await balance(user1Doom)) - before).to.equal(10 * 10 ** 9);
+    });
+
+    it("returns every bet less the fee on a draw", async () => {
+      await resolve(drawPDA, { draw: {} });
+
+      const before = await balance(user2Life);
+      const statsBefore = await program.account.userStats.fetch(findUserStats(user2.publicKey));
+
+      await program.methods
+        .claimWinnings()
+        .accounts(claimAccounts(drawPDA, user2, user2Doom, user2Life))
+        .signers([user2])
+        .rpc();
+
+      const stake = 20 * 10 ** 9;
+      expect((await balance(user2Life)) - before).to.equal(stake - (stake * FEE_BASIS_POINTS) / 10000);
+
+      // A draw counts as neither a win nor a loss
+      const stats = await program.account.userStats.fetch(findUserStats(user2.publicKey));
+      expect(stats.wins.toNumber()).to.equal(statsBefore.wins.toNumber());
+      expect(stats.losses.toNumber()).to.equal(statsBefore.losses.toNumber());
+    });
+  });
+
+  describe("creation bond and creator fees", () => {
+    const cleanId = new anchor.BN(110);
+    const abusiveId = new anchor.BN(111);
+    const CREATION_BOND = 10 * 10 ** 9;
+    const CREATOR_FEE_BPS = 5000;
+    let cleanPDA: PublicKey;
+    let abusivePDA: PublicKey;
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    const findEvent = (eventId: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    const findVault = (seed: string, event: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed), event.toBuffer()], program.programId)[0];
+    const findUserBet = (event: PublicKey, user: Keypair) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), event.toBuffer(), user.publicKey.toBuffer()],
+        program.programId
+      )[0];
+
+    const balance = async (account: PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    const createEvent = (eventId: anchor.BN, deadline: number, creatorDoomAccount: PublicKey | null) => {
+      const event = findEvent(eventId);
+      return program.methods
+        .createEvent(
+          eventId,
+          "Bonded event",
+          "Creator escrows a DOOM bond",
+          new anchor.BN(deadline),
+          new anchor.BN(deadline + 3600),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: findVault("vault_doom", event),
+          lifeVault: findVault("vault_life", event),
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount,
+          bondVault: creatorDoomAccount ? bondVaultPDA : null,
+        })
+        .signers([user1])
+        .rpc();
+    };
+
+    const updateCreatorSettings = async (creationBond: number, creatorFeeBps: number) => {
+      await program.methods
+        .updateCreatorSettings(new anchor.BN(creationBond), creatorFeeBps)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+      await applyPendingConfig();
+    };
+
+    const returnBond = (event: PublicKey) =>
+      program.methods
+        .returnCreationBond()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event,
+          creatorDoomAccount: user1Doom,
+          bondVault: bondVaultPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    before(async () => {
+      cleanPDA = findEvent(cleanId);
+      abusivePDA = findEvent(abusiveId);
+
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      await updateCreatorSettings(CREATION_BOND, CREATOR_FEE_BPS);
+    });
+
+    after(async () => {
+      await updateCreatorSettings(0, 0);
+    });
+
+    it("fails to create an event without posting the bond", async () => {
+      try {
+        await createEvent(cleanId, Math.floor(Date.now() / 1000) + 4, null);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("CreationBondRequired");
+      }
+    });
+
+    it("escrows the creation bond", async () => {
+      const before = await balance(user1Doom);
+      await createEvent(cleanId, Math.floor(Date.now() / 1000) + 4, user1Doom);
+
+      expect(before - (await balance(user1Doom))).to.equal(CREATION_BOND);
+      const event = await program.account.predictionEvent.fetch(cleanPDA);
+      expect(event.creationBond.toNumber()).to.equal(CREATION_BOND);
+    });
+
+    it("pays the creator a cut of the fee and returns the bond on resolution", async () => {
+      for (const [user, outcome, userDoom, userLife] of [
+        [user2, { doom: {} }, user2Doom, user2Life],
+        [user1, { life: {} }, user1Doom, user1Life],
+      ] as [Keypair, object, PublicKey, PublicKey][]) {
+        await program.methods
+          .placeBet(outcome as any, new anchor.BN(10 * 10 ** 9), null)
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: cleanPDA,
+            userBet: findUserBet(cleanPDA, user),
+            userDoomAccount: userDoom,
+            userLifeAccount: userLife,
+            doomVault: findVault("vault_doom", cleanPDA),
+            lifeVault: findVault("vault_life", cleanPDA),
+            userStats: findUserStats(user.publicKey),
+            user: user.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            eventOutcomes: null,
+            outcomeVault: null,
+            shareMint: null,
+            userShareAccount: null,
+          })
+          .signers([user])
+          .rpc();
+      }
+
+      try {
+        await returnBond(cleanPDA);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventNotFinalized");
+      }
+
+      await sleep(4500);
+      await program.methods
+        .resolveEvent({ doom: {} })
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: cleanPDA,
+          proposerDoomAccount: oracleDoom,
+          bondVault: bondVaultPDA,
+          oracle: oracle.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([oracle])
+        .rpc();
+
+      const feeBefore = await balance(lifeTreasuryPDA);
+      await program.methods
+        .claimWinnings()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: cleanPDA,
+          userBet: findUserBet(cleanPDA, user2),
+          userDoomAccount: user2Doom,
+          userLifeAccount: user2Life,
+          doomVault: findVault("vault_doom", cleanPDA),
+          lifeVault: findVault("vault_life", cleanPDA),
+          doomTreasury: doomTreasuryPDA,
+          lifeTreasury: lifeTreasuryPDA,
+          userStats: findUserStats(user2.publicKey),
+          user: user2.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+        })
+        .signers([user2])
+        .rpc();
+
+      // Fee on the 10 LIFE share, split evenly between platform and creator
+      const fee = (10 * 10 ** 9 * FEE_BASIS_POINTS) / 10000;
+      expect((await balance(lifeTreasuryPDA)) - feeBefore).to.equal(fee / 2);
+      const event = await program.account.predictionEvent.fetch(cleanPDA);
+      expect(event.creatorLifeFees.toNumber()).to.equal(fee / 2);
+
+      const lifeBefore = await balance(user1Life);
+      await program.methods
+        .claimCreatorFees()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: cleanPDA,
+          creatorDoomAccount: user1Doom,
+          creatorLifeAccount: user1Life,
+          doomVault: findVault("vault_doom", cleanPDA),
+          lifeVault: findVault("vault_life", cleanPDA),
+          creator: user1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user1])
+        .rpc();
+      expect((await balance(user1Life)) - lifeBefore).to.equal(fee / 2);
+
+      const doomBefore = await balance(user1Doom);
+      await returnBond(cleanPDA);
+      expect((await balance(user1Doom)) - doomBefore).to.equal(CREATION_BOND);
+    });
+
+    it("slashes the bond when an event is cancelled for abuse", async () => {
+      await createEvent(abusiveId, Math.floor(Date.now() / 1000) + 3600, user1Doom);
+
+      const feeBefore = await balance(doomTreasuryPDA);
+      await program.methods
+        .cancelEventForAbuse()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: abusivePDA,
+          bondVault: bondVaultPDA,
+          doomTreasury: doomTreasuryPDA,
+          authority: authority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc();
+
+      expect((await balance(doomTreasuryPDA)) - feeBefore).to.equal(CREATION_BOND);
+      const event = await program.account.predictionEvent.fetch(abusivePDA);
+      expect(event.status).to.deep.equal({ cancelled: {} });
+
+      try {
+        await returnBond(abusivePDA);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NoCreationBond");
+      }
+    });
+  });
+
+  describe("withdraw_fees", () => {
+    let authorityDoom: PublicKey;
+
+    const balance = async (account: PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    const withdraw = (amount: number, signer: Keypair) =>
+      program.methods
+        .withdrawFees(new anchor.BN(amount))
+        .accounts({
+          platformConfig: platformConfigPDA,
+          mint: doomMint,
+          treasury: doomTreasuryPDA,
+          destination: authorityDoom,
+          authority: signer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc({ commitment: "confirmed" });
+
+    before(async () => {
+      authorityDoom = (await getOrCreateAssociatedTokenAccount(provider.connection, authority, doomMint, authority.publicKey)).address;
+    });
+
+    it("fails when not authority", async () => {
+      try {
+        await withdraw(1, user1);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+
+    it("fails to withdraw more than the treasury holds", async () => {
+      try {
+        await withdraw((await balance(doomTreasuryPDA)) + 1, authority);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InsufficientTreasuryBalance");
+      }
+    });
+
+    it("withdraws collected fees with a receipt", async () => {
+      const collected = await balance(doomTreasuryPDA);
+      const before = await balance(authorityDoom);
+
+      const signature = await withdraw(collected, authority);
+
+      expect((await balance(authorityDoom)) - before).to.equal(collected);
+      expect(await balance(doomTreasuryPDA)).to.equal(0);
+
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const parser = new anchor.EventParser(program.programId, program.coder);
+      const receipts = [...parser.parseLogs(tx.meta.logMessages)];
+      expect(receipts).to.have.length(1);
+      expect(receipts[0].name).to.equal("FeesWithdrawn");
+      expect(receipts[0].data.amount.toNumber()).to.equal(collected);
+      expect(receipts[0].data.destination.toString()).to.equal(authorityDoom.toString());
+    });
+  });
+
+  describe("authority transfer and roles", () => {
+    const eventId = new anchor.BN(120);
+    let eventPDA: PublicKey;
+    let newAuthority: Keypair;
+    let moderator: Keypair;
+
+    const setRoles = (pauser: PublicKey | null, feeManager: PublicKey | null, eventModerator: PublicKey | null, signer = authority) =>
+      program.methods
+        .setRoles(pauser, feeManager, eventModerator)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+
+    const proposeAuthority = (proposed: PublicKey, signer: Keypair) =>
+      program.methods
+        .proposeAuthority(proposed)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+
+    const acceptAuthority = (signer: Keypair) =>
+      program.methods
+        .acceptAuthority()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          newAuthority: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+
+    const cancel = (signer: Keypair) =>
+      program.methods
+        .cancelEvent()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          authority: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const findVault = (seed: string) =>
+        PublicKey.findProgramAddressSync([Buffer.from(seed), eventPDA.toBuffer()], program.programId)[0];
+
+      newAuthority = Keypair.generate();
+      moderator = Keypair.generate();
+      for (const account of [newAuthority, moderator]) {
+        const sig = await provider.connection.requestAirdrop(account.publicKey, LAMPORTS_PER_SOL);
+        await provider.connection.confirmTransaction(sig);
+      }
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "Moderated event",
+          "Cancelled by the event moderator",
+          new anchor.BN(now + 3600),
+          new anchor.BN(now + 7200),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+    });
+
+    it("restricts each instruction to its role", async () => {
+      await setRoles(user2.publicKey, user2.publicKey, moderator.publicKey);
+
+      // Authority no longer holds the delegated roles
+      for (const attempt of [
+        () => cancel(authority),
+        () =>
+          program.methods
+            .updatePlatform(100, null, null, null, null, null)
+            .accounts({ platformConfig: platformConfigPDA, authority: authority.publicKey })
+            .signers([authority])
+            .rpc(),
+        () =>
+          program.methods
+            .updatePlatform(null, null, PAUSE_ALL, null, null, null)
+            .accounts({ platformConfig: platformConfigPDA, authority: authority.publicKey })
+            .signers([authority])
+            .rpc(),
+      ]) {
+        try {
+          await attempt();
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.message).to.include("Unauthorized");
+        }
+      }
+
+      await cancel(moderator);
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ cancelled: {} });
+
+      try {
+        await setRoles(null, null, user2.publicKey, user2);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      await setRoles(authority.publicKey, authority.publicKey, authority.publicKey);
+      const config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.moderator.toString()).to.equal(authority.publicKey.toString());
+    });
+
+    it("transfers authority in two steps", async () => {
+      try {
+        await acceptAuthority(newAuthority);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NotPendingAuthority");
+      }
+
+      await proposeAuthority(newAuthority.publicKey, authority);
+      let config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.authority.toString()).to.equal(authority.publicKey.toString());
+      expect(config.pendingAuthority.toString()).to.equal(newAuthority.publicKey.toString());
+
+      try {
+        await acceptAuthority(user1);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NotPendingAuthority");
+      }
+
+      await acceptAuthority(newAuthority);
+      config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.authority.toString()).to.equal(newAuthority.publicKey.toString());
+      expect(config.pendingAuthority).to.be.null;
+
+      try {
+        await proposeAuthority(authority.publicKey, authority);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      // Hand authority back for the remaining tests
+      await proposeAuthority(authority.publicKey, newAuthority);
+      await acceptAuthority(authority);
+      config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.authority.toString()).to.equal(authority.publicKey.toString());
+    });
+  });
+
+  describe("timelocked config changes", () => {
+    const eventId = new anchor.BN(130);
+    const CONFIG_DELAY = 3;
+    let eventPDA: PublicKey;
+
+    const update = (feeBasisPoints: number | null, configDelay: number | null) =>
+      program.methods
+        .updatePlatform(
+          feeBasisPoints,
+          null,
+          null,
+          null,
+          null,
+          configDelay === null ? null : new anchor.BN(configDelay)
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const findVault = (seed: string) =>
+        PublicKey.findProgramAddressSync([Buffer.from(seed), eventPDA.toBuffer()], program.programId)[0];
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "Event with fixed terms",
+          "Keeps the fee it was created with",
+          new anchor.BN(now + 3600),
+          new anchor.BN(now + 7200),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+    });
+
+    it("applies a lengthened delay at once", async () => {
+      await update(null, CONFIG_DELAY);
+
+      const config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.configDelay.toNumber()).to.equal(CONFIG_DELAY);
+      expect(config.pendingConfigDelay).to.be.null;
+    });
+
+    it("queues a fee change until the delay has passed", async () => {
+      await update(500, null);
+
+      let config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.feeBasisPoints).to.equal(FEE_BASIS_POINTS);
+      expect(config.pendingFeeBasisPoints).to.equal(500);
+
+      try {
+        await applyPendingConfig();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PendingConfigNotReady");
+      }
+
+      await sleep((CONFIG_DELAY + 1) * 1000);
+      await applyPendingConfig();
+
+      config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.feeBasisPoints).to.equal(500);
+      expect(config.pendingFeeBasisPoints).to.be.null;
+
+      try {
+        await applyPendingConfig();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NoPendingConfig");
+      }
+    });
+
+    it("keeps the fee snapshotted at creation on existing events", async () => {
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.feeBasisPoints).to.equal(FEE_BASIS_POINTS);
+    });
+
+    it("queues a creator fee share change and keeps it off existing events", async () => {
+      const updateCreatorFee = (creatorFeeBps: number) =>
+        program.methods
+          .updateCreatorSettings(null, creatorFeeBps)
+          .accounts({
+            platformConfig: platformConfigPDA,
+            authority: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc();
+
+      await updateCreatorFee(2000);
+      let config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.creatorFeeBps).to.equal(0);
+      expect(config.pendingCreatorFeeBps).to.equal(2000);
+
+      try {
+        await applyPendingConfig();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PendingConfigNotReady");
+      }
+
+      await sleep((CONFIG_DELAY + 1) * 1000);
+      await applyPendingConfig();
+      config = await program.account.platformConfig.fetch(platformConfigPDA);
+      expect(config.creatorFeeBps).to.equal(2000);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.creatorFeeBps).to.equal(0);
+
+      await updateCreatorFee(0);
+      await sleep((CONFIG_DELAY + 1) * 1000);
+      await applyPendingConfig();
+    });
+
+    after(async () => {
+      // Shortening the delay is itself timelocked
+      await update(FEE_BASIS_POINTS, 0);
+      await sleep((CONFIG_DELAY + 1) * 1000);
+      await applyPendingConfig();
+    });
+  });
+
+  describe("granular pause", () => {
+    const eventId = new anchor.BN(140);
+    let eventPDA: PublicKey;
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+
+    const findVault = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed), eventPDA.toBuffer()], program.programId)[0];
+
+    const setPaused = (flags: number) =>
+      program.methods
+        .updatePlatform(null, null, flags, null, null, null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    const setEventPaused = (paused: boolean, signer = authority) =>
+      program.methods
+        .setEventPaused(paused)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          pauser: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+
+    const placeBet = () =>
+      program.methods
+        .placeBet({ doom: {} }, new anchor.BN(10 ** 9), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: PublicKey.findProgramAddressSync(
+            [Buffer.from("user_bet"), eventPDA.toBuffer(), user1.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          userDoomAccount: user1Doom,
+          userLifeAccount: user1Life,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user1.publicKey),
+          user: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user1])
+        .rpc();
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+    });
+
+    it("pauses betting without halting event creation", async () => {
+      await setPaused(PAUSE_BETTING);
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "Event created while betting is paused",
+          "Creation is not affected by the betting flag",
+          new anchor.BN(now + 3600),
+          new anchor.BN(now + 7200),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+
+      try {
+        await placeBet();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PlatformPaused");
+      }
+
+      await setPaused(0);
+    });
+
+    it("fails with unknown pause flags", async () => {
+      try {
+        await setPaused(1 << 7);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidPauseFlags");
+      }
+    });
+
+    it("pauses a single event", async () => {
+      try {
+        await setEventPaused(true, user1);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      await setEventPaused(true);
+      try {
+        await placeBet();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventPaused");
+      }
+
+      await setEventPaused(false);
+      await placeBet();
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.doomPool.toNumber()).to.equal(10 ** 9);
+    });
+  });
+
+  describe("close_user_bet / close_event", () => {
+    const eventId = new anchor.BN(150);
+    let eventPDA: PublicKey;
+    let userBetPDA: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    const findVault = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed), eventPDA.toBuffer()], program.programId)[0];
+
+    const closeUserBet = () =>
+      program.methods
+        .closeUserBet()
+        .accounts({
+          event: eventPDA,
+          userBet: userBetPDA,
+          user: user2.publicKey,
+          creator: user1.publicKey,
+        })
+        .signers([user2])
+        .rpc();
+
+    const closeEvent = () =>
+      program.methods
+        .closeEvent()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          creator: user1.publicKey,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          doomTreasury: doomTreasuryPDA,
+          lifeTreasury: lifeTreasuryPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+        })
+        .rpc();
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      [userBetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "Event to close",
+          "Cancelled, refunded and closed",
+          new anchor.BN(now + 3600),
+          new anchor.BN(now + 7200),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+
+      await program.methods
+        .placeBet({ life: {} }, new anchor.BN(10 ** 9), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: userBetPDA,
+          userDoomAccount: user2Doom,
+          userLifeAccount: user2Life,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user2.publicKey),
+          user: user2.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user2])
+        .rpc();
+    });
+
+    it("fails to close an unsettled bet or an active event", async () => {
+      try {
+        await closeUserBet();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("BetNotSettled");
+      }
+
+      try {
+        await closeEvent();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventNotFinalized");
+      }
+    });
+
+    it("fails to close the event while a bet is still open", async () => {
+      await program.methods
+        .cancelEvent()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+      await program.methods
+        .claimRefund()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: userBetPDA,
+          userDoomAccount: user2Doom,
+          userLifeAccount: user2Life,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          user: user2.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+        })
+        .signers([user2])
+        .rpc();
+
+      try {
+        await closeEvent();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventNotSettled");
+      }
+    });
+
+    it("closes the bet and then the event, returning rent", async () => {
+      const userBefore = await provider.connection.getBalance(user2.publicKey);
+      await closeUserBet();
+      expect(await provider.connection.getBalance(user2.publicKey)).to.be.greaterThan(userBefore);
+      expect(await provider.connection.getAccountInfo(userBetPDA)).to.be.null;
+
+      const creatorBefore = await provider.connection.getBalance(user1.publicKey);
+      await closeEvent();
+      expect(await provider.connection.getBalance(user1.publicKey)).to.be.greaterThan(creatorBefore);
+      expect(await provider.connection.getAccountInfo(eventPDA)).to.be.null;
+      expect(await provider.connection.getAccountInfo(findVault("vault_doom"))).to.be.null;
+      expect(await provider.connection.getAccountInfo(findVault("vault_life"))).to.be.null;
+    });
+
+    it("keeps a swept event as a tombstone until its last bet is closed", async () => {
+      const sweptId = new anchor.BN(151);
+      const [sweptPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), sweptId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const [sweptBetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), sweptPDA.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+      const sweptVault = (seed: string) =>
+        PublicKey.findProgramAddressSync([Buffer.from(seed), sweptPDA.toBuffer()], program.programId)[0];
+      const updateSweepWindow = (window: number) =>
+        program.methods
+          .updateBettingRules(null, null, new anchor.BN(window))
+          .accounts({
+            platformConfig: platformConfigPDA,
+            authority: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc();
+      const createSweptEvent = () => {
+        const now = Math.floor(Date.now() / 1000);
+        return program.methods
+          .createEvent(sweptId, "Event to sweep", "Expires with a bet left open", new anchor.BN(now + 2), new anchor.BN(now + 4), null)
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: sweptPDA,
+            doomMint: doomMint,
+            lifeMint: lifeMint,
+            doomVault: sweptVault("vault_doom"),
+            lifeVault: sweptVault("vault_life"),
+            userStats: findUserStats(user1.publicKey),
+            creator: user1.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            resolverAllowlist: null,
+            creatorDoomAccount: null,
+            bondVault: null,
+          })
+          .signers([user1])
+          .rpc();
+      };
+      const placeSweptBet = (amount: number) =>
+        program.methods
+          .placeBet({ doom: {} }, new anchor.BN(amount), null)
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: sweptPDA,
+            userBet: sweptBetPDA,
+            userDoomAccount: user2Doom,
+            userLifeAccount: user2Life,
+            doomVault: sweptVault("vault_doom"),
+            lifeVault: sweptVault("vault_life"),
+            userStats: findUserStats(user2.publicKey),
+            user: user2.publicKey,
+            systemProgram: SystemProgram.programId,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            eventOutcomes: null,
+            outcomeVault: null,
+            shareMint: null,
+            userShareAccount: null,
+          })
+          .signers([user2])
+          .rpc();
+
+      await updateSweepWindow(0);
+      await createSweptEvent();
+      await placeSweptBet(10 ** 9);
+      await sleep(6000);
+      await program.methods
+        .expireEvent()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: sweptPDA,
+          bondVault: null,
+          proposerDoomAccount: null,
+          disputerDoomAccount: null,
+          tokenProgram: null,
+        })
+        .rpc();
+
+      await program.methods
+        .closeEvent()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: sweptPDA,
+          creator: user1.publicKey,
+          doomVault: sweptVault("vault_doom"),
+          lifeVault: sweptVault("vault_life"),
+          doomTreasury: doomTreasuryPDA,
+          lifeTreasury: lifeTreasuryPDA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+        })
+        .rpc();
+      const tombstone = await program.account.predictionEvent.fetch(sweptPDA);
+      expect(tombstone.status).to.deep.equal({ swept: {} });
+      expect(tombstone.outstandingBets).to.equal(1);
+      expect(await provider.connection.getAccountInfo(sweptVault("vault_doom"))).to.be.null;
+
+      // The id can't be reused while the old bet is still open
+      try {
+        await createSweptEvent();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("already in use");
+      }
+
+      const creatorBefore = await provider.connection.getBalance(user1.publicKey);
+      await program.methods
+        .closeUserBet()
+        .accounts({
+          event: sweptPDA,
+          userBet: sweptBetPDA,
+          user: user2.publicKey,
+          creator: user1.publicKey,
+        })
+        .signers([user2])
+        .rpc();
+      expect(await provider.connection.getAccountInfo(sweptBetPDA)).to.be.null;
+      expect(await provider.connection.getAccountInfo(sweptPDA)).to.be.null;
+      expect(await provider.connection.getBalance(user1.publicKey)).to.be.greaterThan(creatorBefore);
+
+      await updateSweepWindow(30 * 24 * 60 * 60);
+      await createSweptEvent();
+      await placeSweptBet(2 * 10 ** 9);
+      const bet = await program.account.userBet.fetch(sweptBetPDA);
+      expect(bet.doomAmount.toNumber()).to.equal(2 * 10 ** 9);
+      const event = await program.account.predictionEvent.fetch(sweptPDA);
+      expect(event.outstandingBets).to.equal(1);
+    });
+  });
+
+  describe("bet limits", () => {
+    const eventId = new anchor.BN(160);
+    let eventPDA: PublicKey;
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    const limits = {
+      minBet: new anchor.BN(2 * 10 ** 9),
+      maxBet: new anchor.BN(5 * 10 ** 9),
+      maxUserStake: new anchor.BN(8 * 10 ** 9),
+      maxSideShareBps: 7000,
+    };
+
+    const findVault = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed), eventPDA.toBuffer()], program.programId)[0];
+
+    const setEventBetLimits = (signer: Keypair) =>
+      program.methods
+        .setEventBetLimits(limits)
+        .accounts({
+          event: eventPDA,
+          creator: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+
+    const bet = (user: Keypair, outcome: object, amount: number, userDoom: PublicKey, userLife: PublicKey) =>
+      program.methods
+        .placeBet(outcome as any, new anchor.BN(amount), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: PublicKey.findProgramAddressSync(
+            [Buffer.from("user_bet"), eventPDA.toBuffer(), user.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          userDoomAccount: userDoom,
+          userLifeAccount: userLife,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user.publicKey),
+          user: user.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user])
+        .rpc();
+
+    const expectError = async (promise: Promise<unknown>, code: string) => {
+      try {
+        await promise;
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include(code);
+      }
+    };
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "Event with bet limits",
+          "Caps bet sizes, user stakes and pool share",
+          new anchor.BN(now + 3600),
+          new anchor.BN(now + 7200),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+    });
+
+    it("copies the platform defaults into new events", async () => {
+      const config = await program.account.platformConfig.fetch(platformConfigPDA);
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.betLimits.minBet.toString()).to.equal(config.betLimits.minBet.toString());
+      expect(event.betLimits.maxBet.toNumber()).to.equal(0);
+    });
+
+    it("fails to set invalid platform defaults", async () => {
+      await expectError(
+        program.methods
+          .updateBetLimits({ ...limits, minBet: new anchor.BN(0) })
+          .accounts({
+            platformConfig: platformConfigPDA,
+            authority: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc(),
+        "InvalidBetLimits"
+      );
+    });
+
+    it("lets only the creator set the event's limits", async () => {
+      await expectError(setEventBetLimits(user2), "Unauthorized");
+      await setEventBetLimits(user1);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.betLimits.maxSideShareBps).to.equal(7000);
+    });
+
+    it("enforces the per-bet, per-user and pool share limits", async () => {
+      await expectError(bet(user1, { doom: {} }, 10 ** 9, user1Doom, user1Life), "BetBelowMinimum");
+      await expectError(bet(user1, { doom: {} }, 6 * 10 ** 9, user1Doom, user1Life), "BetAboveMaximum");
+
+      await bet(user1, { doom: {} }, 5 * 10 ** 9, user1Doom, user1Life);
+      await bet(user2, { life: {} }, 5 * 10 ** 9, user2Doom, user2Life);
+
+      await expectError(bet(user1, { doom: {} }, 4 * 10 ** 9, user1Doom, user1Life), "UserStakeLimitExceeded");
+      await bet(user1, { doom: {} }, 3 * 10 ** 9, user1Doom, user1Life);
+
+      // DOOM would hold 13 of 18 tokens, above the 70% cap
+      await expectError(bet(user2, { doom: {} }, 5 * 10 ** 9, user2Doom, user2Life), "SideShareLimitExceeded");
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.doomPool.toNumber()).to.equal(8 * 10 ** 9);
+      expect(event.lifePool.toNumber()).to.equal(5 * 10 ** 9);
+    });
+
+    it("fails to change the limits once betting has started", async () => {
+      await expectError(setEventBetLimits(user1), "BetLimitsLocked");
+    });
+  });
+
+  describe("betting cutoff", () => {
+    const eventId = new anchor.BN(170);
+    const cutoffEventId = new anchor.BN(171);
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+
+    const findEvent = (id: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), id.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      )[0];
+    const findVault = (seed: string, event: PublicKey) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed), event.toBuffer()], program.programId)[0];
+
+    const createEvent = (id: anchor.BN, deadline: number) => {
+      const event = findEvent(id);
+      return program.methods
+        .createEvent(
+          id,
+          "Event with a betting cutoff",
+          "Betting closes before the deadline",
+          new anchor.BN(deadline),
+          new anchor.BN(deadline + 3600),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: findVault("vault_doom", event),
+          lifeVault: findVault("vault_life", event),
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+    };
+
+    const setBettingClosesAt = (closesAt: number, signer = user1) =>
+      program.methods
+        .setBettingClosesAt(new anchor.BN(closesAt))
+        .accounts({
+          event: findEvent(eventId),
+          creator: signer.publicKey,
+        })
+        .signers([signer])
+        .rpc();
+
+    const updateBettingCutoff = (cutoff: number) =>
+      program.methods
+        .updateBettingRules(new anchor.BN(cutoff), null, null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    const placeBet = () => {
+      const event = findEvent(eventId);
+      return program.methods
+        .placeBet({ doom: {} }, new anchor.BN(10 ** 9), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event,
+          userBet: PublicKey.findProgramAddressSync(
+            [Buffer.from("user_bet"), event.toBuffer(), user1.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          userDoomAccount: user1Doom,
+          userLifeAccount: user1Life,
+          doomVault: findVault("vault_doom", event),
+          lifeVault: findVault("vault_life", event),
+          userStats: findUserStats(user1.publicKey),
+          user: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user1])
+        .rpc();
+    };
+
+    before(async () => {
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+    });
+
+    it("closes betting on new events before the deadline", async () => {
+      try {
+        await updateBettingCutoff(-1);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidBettingCutoff");
+      }
+
+      await updateBettingCutoff(600);
+      try {
+        try {
+          await createEvent(cutoffEventId, Math.floor(Date.now() / 1000) + 300);
+          expect.fail("Should have thrown an error");
+        } catch (error) {
+          expect(error.message).to.include("InvalidBettingCutoff");
+        }
+
+        const deadline = Math.floor(Date.now() / 1000) + 3600;
+        await createEvent(cutoffEventId, deadline);
+        const event = await program.account.predictionEvent.fetch(findEvent(cutoffEventId));
+        expect(event.bettingClosesAt.toNumber()).to.equal(deadline - 600);
+      } finally {
+        await updateBettingCutoff(0);
+      }
+    });
+
+    it("lets the creator move the cutoff up to the deadline", async () => {
+      const now = Math.floor(Date.now() / 1000);
+      await createEvent(eventId, now + 3600);
+
+      try {
+        await setBettingClosesAt(now + 2, user2);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      try {
+        await setBettingClosesAt(now + 7200);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidBettingCutoff");
+      }
+
+      await setBettingClosesAt(now + 1800);
+      const event = await program.account.predictionEvent.fetch(findEvent(eventId));
+      expect(event.bettingClosesAt.toNumber()).to.equal(now + 1800);
+    });
+
+    it("only moves the cutoff earlier once bets are placed", async () => {
+      await placeBet();
+
+      const now = Math.floor(Date.now() / 1000);
+      try {
+        await setBettingClosesAt(now + 2400);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("BettingCutoffLocked");
+      }
+
+      await setBettingClosesAt(now + 2);
+      const event = await program.account.predictionEvent.fetch(findEvent(eventId));
+      expect(event.bettingClosesAt.toNumber()).to.equal(now + 2);
+    });
+
+    it("rejects bets after the cutoff", async () => {
+      await sleep(3000);
+      try {
+        await placeBet();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("BettingClosed");
+      }
+
+      try {
+        await setBettingClosesAt(Math.floor(Date.now() / 1000) + 60);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("BettingClosed");
+      }
+    });
+  });
+
+  describe("withdraw_bet", () => {
+    const eventId = new anchor.BN(180);
+    const EXIT_PENALTY_BPS = 1000;
+    let deadline: number;
+    let eventPDA: PublicKey;
+    let user1BetPDA: PublicKey;
+    let user2BetPDA: PublicKey;
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    const findVault = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed), eventPDA.toBuffer()], program.programId)[0];
+    const balance = async (account: PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    const updateExitPenalty = (penaltyBps: number) =>
+      program.methods
+        .updateBettingRules(null, penaltyBps, null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+    const bet = (user: Keypair, userBet: PublicKey, userDoom: PublicKey, userLife: PublicKey, outcome: object = { doom: {} }) =>
+      program.methods
+        .placeBet(outcome as any, new anchor.BN(10 * 10 ** 9), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet,
+          userDoomAccount: userDoom,
+          userLifeAccount: userLife,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user.publicKey),
+          user: user.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user])
+        .rpc();
+
+    const withdraw = (user: Keypair, userBet: PublicKey, userDoom: PublicKey, userLife: PublicKey, amount: number) =>
+      program.methods
+        .withdrawBet({ doom: {} }, new anchor.BN(amount))
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet,
+          userDoomAccount: userDoom,
+          userLifeAccount: userLife,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user.publicKey),
+          user: user.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+        })
+        .signers([user])
+        .rpc();
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      [user1BetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user1.publicKey.toBuffer()],
+        program.programId
+      );
+      [user2BetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      await updateExitPenalty(EXIT_PENALTY_BPS);
+      const now = Math.floor(Date.now() / 1000);
+      deadline = now + 12;
+      await program.methods
+        .createEvent(
+          eventId,
+          "Event with early exits",
+          "Bettors withdraw before the cutoff",
+          new anchor.BN(deadline),
+          new anchor.BN(now + 7200),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+
+      await bet(user1, user1BetPDA, user1Doom, user1Life);
+      await bet(user2, user2BetPDA, user2Doom, user2Life);
+    });
+
+    after(async () => {
+      await updateExitPenalty(500);
+    });
+
+    it("fails to withdraw while withdrawals are paused", async () => {
+      const setPaused = (flags: number) =>
+        program.methods
+          .updatePlatform(null, null, flags, null, null, null)
+          .accounts({
+            platformConfig: platformConfigPDA,
+            authority: authority.publicKey,
+          })
+          .signers([authority])
+          .rpc();
+
+      await setPaused(PAUSE_WITHDRAWALS);
+      try {
+        await withdraw(user1, user1BetPDA, user1Doom, user1Life, 10 ** 9);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("PlatformPaused");
+      }
+      await setPaused(0);
+    });
+
+    it("fails to withdraw more than the stake", async () => {
+      try {
+        await withdraw(user1, user1BetPDA, user1Doom, user1Life, 11 * 10 ** 9);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InsufficientStake");
+      }
+    });
+
+    it("withdraws part of a position, moving the penalty to the penalty pool", async () => {
+      const before = await balance(user1Doom);
+      const statsBefore = await program.account.userStats.fetch(findUserStats(user1.publicKey));
+      await withdraw(user1, user1BetPDA, user1Doom, user1Life, 4 * 10 ** 9);
+
+      expect((await balance(user1Doom)) - before).to.equal(36 * 10 ** 8);
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.doomPool.toNumber()).to.equal(16 * 10 ** 9);
+      expect(event.doomPenaltyPool.toNumber()).to.equal(4 * 10 ** 8);
+      expect(event.totalBettors).to.equal(2);
+      const userBet = await program.account.userBet.fetch(user1BetPDA);
+      expect(userBet.doomAmount.toNumber()).to.equal(6 * 10 ** 9);
+      const stats = await program.account.userStats.fetch(findUserStats(user1.publicKey));
+      expect(statsBefore.totalWagered.sub(stats.totalWagered).toNumber()).to.equal(4 * 10 ** 9);
+      expect(stats.totalLost.sub(statsBefore.totalLost).toNumber()).to.equal(4 * 10 ** 8);
+      expect(stats.totalBets.toNumber()).to.equal(statsBefore.totalBets.toNumber());
+    });
+
+    it("closes the bet on a full exit", async () => {
+      const statsBefore = await program.account.userStats.fetch(findUserStats(user2.publicKey));
+      await withdraw(user2, user2BetPDA, user2Doom, user2Life, 10 * 10 ** 9);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.doomPool.toNumber()).to.equal(6 * 10 ** 9);
+      expect(event.doomPenaltyPool.toNumber()).to.equal(14 * 10 ** 8);
+      expect(event.totalBettors).to.equal(1);
+      expect(event.outstandingBets).to.equal(1);
+      expect(await provider.connection.getAccountInfo(user2BetPDA)).to.be.null;
+      const stats = await program.account.userStats.fetch(findUserStats(user2.publicKey));
+      expect(statsBefore.totalBets.sub(stats.totalBets).toNumber()).to.equal(1);
+      expect(statsBefore.totalWagered.sub(stats.totalWagered).toNumber()).to.equal(10 * 10 ** 9);
+      expect(stats.totalLost.sub(statsBefore.totalLost).toNumber()).to.equal(10 ** 9);
+    });
+
+    it("pays the penalty pool to the winners and settles the event", async () => {
+      await bet(user2, user2BetPDA, user2Doom, user2Life, { life: {} });
+
+      await sleep(Math.max(0, deadline * 1000 - Date.now()) + 1000);
+      await program.methods
+        .resolveEvent({ doom: {} })
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          proposerDoomAccount: oracleDoom,
+          bondVault: bondVaultPDA,
+          oracle: oracle.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([oracle])
+        .rpc();
+
+      const { feeBasisPoints } = await program.account.predictionEvent.fetch(eventPDA);
+      const doomBefore = await balance(user1Doom);
+      const lifeBefore = await balance(user1Life);
+      await program.methods
+        .claimWinnings()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: user1BetPDA,
+          userDoomAccount: user1Doom,
+          userLifeAccount: user1Life,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          doomTreasury: doomTreasuryPDA,
+          lifeTreasury: lifeTreasuryPDA,
+          userStats: findUserStats(user1.publicKey),
+          user: user1.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+        })
+        .signers([user1])
+        .rpc();
+
+      // Sole winner: 6 DOOM stake, the whole 1.4 DOOM penalty pool and the
+      // 10 LIFE losing pool, fees taken from everything but the stake
+      const penalty = 14 * 10 ** 8;
+      const losingPool = 10 * 10 ** 9;
+      expect((await balance(user1Doom)) - doomBefore).to.equal(
+        6 * 10 ** 9 + penalty - (penalty * feeBasisPoints) / 10000
+      );
+      expect((await balance(user1Life)) - lifeBefore).to.equal(
+        losingPool - (losingPool * feeBasisPoints) / 10000
+      );
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ settled: {} });
+      expect(await balance(findVault("vault_doom"))).to.equal(event.creatorDoomFees.toNumber());
+      expect(await balance(findVault("vault_life"))).to.equal(event.creatorLifeFees.toNumber());
+    });
+  });
+
+  describe("outcome shares", () => {
+    const eventId = new anchor.BN(190);
+    const STAKE = 10 * 10 ** 9;
+    let eventPDA: PublicKey;
+    let doomShareMint: PublicKey;
+    let lifeShareMint: PublicKey;
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+    let user1DoomShares: PublicKey;
+    let user1LifeShares: PublicKey;
+    let user2DoomShares: PublicKey;
+
+    const findVault = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed), eventPDA.toBuffer()], program.programId)[0];
+    const balance = async (account: PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    const bet = (user: Keypair, outcome: object, userDoom: PublicKey, userLife: PublicKey, shareMint: PublicKey, userShares: PublicKey) =>
+      program.methods
+        .placeBet(outcome as any, new anchor.BN(STAKE), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: PublicKey.findProgramAddressSync(
+            [Buffer.from("user_bet"), eventPDA.toBuffer(), user.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          userDoomAccount: userDoom,
+          userLifeAccount: userLife,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user.publicKey),
+          user: user.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint,
+          userShareAccount: userShares,
+        })
+        .signers([user])
+        .rpc();
+
+    const closeUserBet = (user: Keypair) =>
+      program.methods
+        .closeUserBet()
+        .accounts({
+          event: eventPDA,
+          userBet: PublicKey.findProgramAddressSync(
+            [Buffer.from("user_bet"), eventPDA.toBuffer(), user.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          user: user.publicKey,
+          creator: user1.publicKey,
+        })
+        .signers([user])
+        .rpc();
+
+    const redeem = (holder: Keypair, outcome: object, amount: number, holderDoom: PublicKey, holderLife: PublicKey, shareMint: PublicKey, holderShares: PublicKey) =>
+      program.methods
+        .redeemShares(outcome as any, new anchor.BN(amount))
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          shareMint,
+          holderShareAccount: holderShares,
+          holderDoomAccount: holderDoom,
+          holderLifeAccount: holderLife,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          doomTreasury: doomTreasuryPDA,
+          lifeTreasury: lifeTreasuryPDA,
+          holder: holder.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([holder])
+        .rpc();
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      doomShareMint = findVault("share_doom");
+      lifeShareMint = findVault("share_life");
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "Tokenized event",
+          "Positions are held as share tokens",
+          new anchor.BN(now + 4),
+          new anchor.BN(now + 3600),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+    });
+
+    it("creates share mints owned by the event", async () => {
+      await program.methods
+        .createShareMints()
+        .accounts({
+          event: eventPDA,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomShareMint,
+          lifeShareMint,
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+        })
+        .signers([user1])
+        .rpc();
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.tokenized).to.be.true;
+
+      user1DoomShares = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomShareMint, user1.publicKey)).address;
+      user1LifeShares = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeShareMint, user1.publicKey)).address;
+      user2DoomShares = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomShareMint, user2.publicKey)).address;
+    });
+
+    it("mints shares on each bet", async () => {
+      try {
+        await bet(user2, { doom: {} }, user2Doom, user2Life, lifeShareMint, user1LifeShares);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidShareMint");
+      }
+
+      await bet(user2, { doom: {} }, user2Doom, user2Life, doomShareMint, user2DoomShares);
+      await bet(user1, { life: {} }, user1Doom, user1Life, lifeShareMint, user1LifeShares);
+
+      expect(await balance(user2DoomShares)).to.equal(STAKE);
+      expect(await balance(user1LifeShares)).to.equal(STAKE);
+    });
+
+    it("redeems transferred winning shares by burning them", async () => {
+      await transfer(provider.connection, user2, user2DoomShares, user1DoomShares, user2, 4 * 10 ** 9);
+
+      await sleep(4500);
+      await program.methods
+        .resolveEvent({ doom: {} })
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          proposerDoomAccount: oracleDoom,
+          bondVault: bondVaultPDA,
+          oracle: oracle.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([oracle])
+        .rpc();
+
+      try {
+        await redeem(user1, { life: {} }, STAKE, user1Doom, user1Life, lifeShareMint, user1LifeShares);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("NotAWinner");
+      }
+
+      const { feeBasisPoints } = await program.account.predictionEvent.fetch(eventPDA);
+      const doomBefore = await balance(user1Doom);
+      const lifeBefore = await balance(user1Life);
+      await redeem(user1, { doom: {} }, 4 * 10 ** 9, user1Doom, user1Life, doomShareMint, user1DoomShares);
+
+      expect((await balance(user1Doom)) - doomBefore).to.equal(4 * 10 ** 9);
+      expect((await balance(user1Life)) - lifeBefore).to.equal(4 * 10 ** 9 * (10000 - feeBasisPoints) / 10000);
+      expect(await balance(user1DoomShares)).to.equal(0);
+    });
+
+    it("fails to close the event while winning shares are unredeemed", async () => {
+      try {
+        await closeUserBet(user2);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("BetNotSettled");
+      }
+
+      try {
+        await program.methods
+          .closeEvent()
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            creator: user1.publicKey,
+            doomVault: findVault("vault_doom"),
+            lifeVault: findVault("vault_life"),
+            doomTreasury: doomTreasuryPDA,
+            lifeTreasury: lifeTreasuryPDA,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            eventOutcomes: null,
+          })
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventNotSettled");
+      }
+      expect(await balance(user2DoomShares)).to.equal(6 * 10 ** 9);
+    });
+
+    it("settles the event once every winning share is redeemed", async () => {
+      await redeem(user2, { doom: {} }, 6 * 10 ** 9, user2Doom, user2Life, doomShareMint, user2DoomShares);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ settled: {} });
+      expect(await balance(user2DoomShares)).to.equal(0);
+
+      await closeUserBet(user2);
+      await closeUserBet(user1);
+      const { outstandingBets } = await program.account.predictionEvent.fetch(eventPDA);
+      expect(outstandingBets).to.equal(0);
+    });
+  });
+
+  describe("LMSR pricing", () => {
+    const eventId = new anchor.BN(200);
+    const LIQUIDITY = 100 * 10 ** 9;
+    const SHARES = 10 * 10 ** 9;
+    let eventPDA: PublicKey;
+    let user1Doom: PublicKey;
+    let user1Life: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    const findVault = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed), eventPDA.toBuffer()], program.programId)[0];
+    const findUserBet = (user: Keypair) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user.publicKey.toBuffer()],
+        program.programId
+      )[0];
+    const balance = async (account: PublicKey) =>
+      Number((await getAccount(provider.connection, account)).amount);
+
+    const enableLmsr = (signer: Keypair) =>
+      program.methods
+        .enableLmsr(new anchor.BN(LIQUIDITY))
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomVault: findVault("vault_doom"),
+          doomTreasury: doomTreasuryPDA,
+          feeManager: signer.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc();
+
+    const buy = (user: Keypair, outcome: object, maxCost: number, userDoom: PublicKey, userLife: PublicKey) =>
+      program.methods
+        .placeBet(outcome as any, new anchor.BN(SHARES), new anchor.BN(maxCost))
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: findUserBet(user),
+          userDoomAccount: userDoom,
+          userLifeAccount: userLife,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user.publicKey),
+          user: user.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user])
+        .rpc();
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      user1Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, doomMint, user1.publicKey)).address;
+      user1Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user1, lifeMint, user1.publicKey)).address;
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      // Fund the treasury that subsidizes the market maker
+      await mintTo(provider.connection, authority, doomMint, doomTreasuryPDA, authority, LIQUIDITY);
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "LMSR event",
+          "Shares are bought from a market maker",
+          new anchor.BN(now + 4),
+          new anchor.BN(now + 3600),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+    });
+
+    it("subsidizes the market maker from the treasury", async () => {
+      try {
+        await enableLmsr(user1);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      await enableLmsr(authority);
+
+      // b * ln 2
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.pricing).to.deep.equal({ lmsr: {} });
+      expect(await balance(findVault("vault_doom"))).to.be.closeTo(69_314_718_055, 2);
+    });
+
+    it("sells shares at the quoted price with a slippage guard", async () => {
+      // 100 * (ln(e^0.1 + 1) - ln 2) DOOM
+      const price = 5_124_947_953;
+      try {
+        await buy(user2, { doom: {} }, 5 * 10 ** 9, user2Doom, user2Life);
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("MaxCostExceeded");
+      }
+
+      const before = await balance(user2Doom);
+      await buy(user2, { doom: {} }, 6 * 10 ** 9, user2Doom, user2Life);
+      expect(before - (await balance(user2Doom))).to.be.closeTo(price, 2);
+
+      // The other side is now cheaper, and is paid in DOOM too
+      const user1Before = await balance(user1Doom);
+      await buy(user1, { life: {} }, 6 * 10 ** 9, user1Doom, user1Life);
+      expect(user1Before - (await balance(user1Doom))).to.be.closeTo(10 ** 10 - price, 2);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.doomPool.toNumber()).to.equal(SHARES);
+      expect(event.lifePool.toNumber()).to.equal(SHARES);
+    });
+
+    it("pays a fixed 1 DOOM per winning share", async () => {
+      await sleep(4500);
+      await program.methods
+        .resolveEvent({ doom: {} })
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          proposerDoomAccount: oracleDoom,
+          bondVault: bondVaultPDA,
+          oracle: oracle.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([oracle])
+        .rpc();
+
+      const { feeBasisPoints } = await program.account.predictionEvent.fetch(eventPDA);
+      const cost = (await program.account.userBet.fetch(findUserBet(user2))).lmsrCost.toNumber();
+      const before = await balance(user2Doom);
+      await program.methods
+        .claimWinnings()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: findUserBet(user2),
+          userDoomAccount: user2Doom,
+          userLifeAccount: user2Life,
+          doomVault: findVault("vault_doom"),
+          lifeVault: findVault("vault_life"),
+          doomTreasury: doomTreasuryPDA,
+          lifeTreasury: lifeTreasuryPDA,
+          userStats: findUserStats(user2.publicKey),
+          user: user2.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+        })
+        .signers([user2])
+        .rpc();
+
+      // The fee is only taken on the profit over what the shares cost
+      const profit = Math.max(SHARES - cost, 0);
+      const fee = Math.floor((profit * feeBasisPoints) / 10000);
+      expect((await balance(user2Doom)) - before).to.equal(SHARES - fee);
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ settled: {} });
+    });
+  });
+
+  describe("cancel_event", () => {
+    const eventId = new anchor.BN(2);
+    let eventPDA: PublicKey;
+    let doomVaultPDA: PublicKey;
+    let lifeVaultPDA: PublicKey;
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+
+      [doomVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_doom"), eventPDA.toBuffer()],
+        program.programId
+      );
+
+      [lifeVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_life"), eventPDA.toBuffer()],
+        program.programId
+      );
+
+      // Create event first
+      const [userStatsPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_stats"), user1.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "Event to Cancel",
+          "This event will be cancelled",
+          new anchor.BN(now + 86400),
+          new anchor.BN(now + 172800),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          userStats: userStatsPDA,
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+    });
+
+    it("cancels an event", async () => {
+      await program.methods
+        .cancelEvent()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ cancelled: {} });
+    });
+
+    it("fails when not authority", async () => {
+      const newEventId = new anchor.BN(3);
+      const [newEventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), newEventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .cancelEvent()
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: newEventPDA,
+            authority: user1.publicKey,
+          })
+          .signers([user1])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  describe("claim_refund", () => {
+    const eventId = new anchor.BN(20);
+    let eventPDA: PublicKey;
+    let doomVaultPDA: PublicKey;
+    let lifeVaultPDA: PublicKey;
+    let userBetPDA: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    const refundAccounts = (event: PublicKey, userBet: PublicKey) => ({
+      platformConfig: platformConfigPDA,
+      event,
+      userBet,
+      userDoomAccount: user2Doom,
+      userLifeAccount: user2Life,
+      doomVault: PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_doom"), event.toBuffer()],
+        program.programId
+      )[0],
+      lifeVault: PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_life"), event.toBuffer()],
+        program.programId
+      )[0],
+      user: user2.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      eventOutcomes: null,
+    });
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      [doomVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_doom"), eventPDA.toBuffer()],
+        program.programId
+      );
+      [lifeVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_life"), eventPDA.toBuffer()],
+        program.programId
+      );
+      [userBetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "Event to Refund",
+          "This event will be cancelled after bets are placed",
+          new anchor.BN(now + 86400),
+          new anchor.BN(now + 172800),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+
+      await program.methods
+        .placeBet({ life: {} }, new anchor.BN(25 * 10 ** 9), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: userBetPDA,
+          userDoomAccount: user2Doom,
+          userLifeAccount: user2Life,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          userStats: findUserStats(user2.publicKey),
+          user: user2.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user2])
+        .rpc();
+    });
+
+    it("fails before the event is cancelled", async () => {
+      try {
+        await program.methods
+          .claimRefund()
+          .accounts(refundAccounts(eventPDA, userBetPDA))
+          .signers([user2])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventNotRefundable");
+      }
+    });
+
+    it("refunds the full stake on a cancelled event", async () => {
+      await program.methods
+        .cancelEvent()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc();
+
+      const before = Number((await getAccount(provider.connection, user2Life)).amount);
+
+      await program.methods
+        .claimRefund()
+        .accounts(refundAccounts(eventPDA, userBetPDA))
+        .signers([user2])
+        .rpc();
+
+      const after = Number((await getAccount(provider.connection, user2Life)).amount);
+      expect(after - before).to.equal(25 * 10 ** 9);
+
+      const bet = await program.account.userBet.fetch(userBetPDA);
+      expect(bet.refunded).to.equal(true);
+
+      const vault = await getAccount(provider.connection, lifeVaultPDA);
+      expect(Number(vault.amount)).to.equal(0);
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ settled: {} });
+    });
+
+    it("fails to refund twice", async () => {
+      try {
+        await program.methods
+          .claimRefund()
+          .accounts(refundAccounts(eventPDA, userBetPDA))
+          .signers([user2])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("AlreadyRefunded");
+      }
+    });
+
+    it("fails on a resolved event", async () => {
+      // user2 lost on event 10, which was resolved and settled above
+      const [resolvedEventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), new anchor.BN(10).toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      const [resolvedBetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), resolvedEventPDA.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .claimRefund()
+          .accounts(refundAccounts(resolvedEventPDA, resolvedBetPDA))
+          .signers([user2])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("EventNotRefundable");
+      }
+    });
+  });
+
+  describe("expire_event", () => {
+    const eventId = new anchor.BN(30);
+    let eventPDA: PublicKey;
+    let doomVaultPDA: PublicKey;
+    let lifeVaultPDA: PublicKey;
+    let userBetPDA: PublicKey;
+    let user2Doom: PublicKey;
+    let user2Life: PublicKey;
+
+    before(async () => {
+      [eventPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("event"), eventId.toArrayLike(Buffer, "le", 8)],
+        program.programId
+      );
+      [doomVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_doom"), eventPDA.toBuffer()],
+        program.programId
+      );
+      [lifeVaultPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("vault_life"), eventPDA.toBuffer()],
+        program.programId
+      );
+      [userBetPDA] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_bet"), eventPDA.toBuffer(), user2.publicKey.toBuffer()],
+        program.programId
+      );
+
+      user2Doom = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, doomMint, user2.publicKey)).address;
+      user2Life = (await getOrCreateAssociatedTokenAccount(provider.connection, user2, lifeMint, user2.publicKey)).address;
+
+      // Resolution window closes a few seconds after creation
+      const now = Math.floor(Date.now() / 1000);
+      await program.methods
+        .createEvent(
+          eventId,
+          "Event to Expire",
+          "The oracle never resolves this event",
+          new anchor.BN(now + 2),
+          new anchor.BN(now + 4),
+          null
+        )
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          doomMint: doomMint,
+          lifeMint: lifeMint,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          userStats: findUserStats(user1.publicKey),
+          creator: user1.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+          resolverAllowlist: null,
+          creatorDoomAccount: null,
+          bondVault: null,
+        })
+        .signers([user1])
+        .rpc();
+
+      await program.methods
+        .placeBet({ life: {} }, new anchor.BN(10 * 10 ** 9), null)
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: userBetPDA,
+          userDoomAccount: user2Doom,
+          userLifeAccount: user2Life,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          userStats: findUserStats(user2.publicKey),
+          user: user2.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+          outcomeVault: null,
+          shareMint: null,
+          userShareAccount: null,
+        })
+        .signers([user2])
+        .rpc();
+    });
+
+    it("fails before the resolution deadline", async () => {
+      try {
+        await program.methods
+          .expireEvent()
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            bondVault: null,
+            proposerDoomAccount: null,
+            disputerDoomAccount: null,
+            tokenProgram: null,
+          })
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("ResolutionDeadlineNotPassed");
+      }
+    });
+
+    it("rejects resolution after the resolution deadline", async () => {
+      await sleep(6000);
+
+      try {
+        await program.methods
+          .resolveEvent({ life: {} })
+          .accounts({
+            platformConfig: platformConfigPDA,
+            event: eventPDA,
+            proposerDoomAccount: oracleDoom,
+            bondVault: bondVaultPDA,
+            oracle: oracle.publicKey,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([oracle])
+          .rpc();
+        expect.fail("Should have thrown an error");
+      } catch (error) {
+        expect(error.message).to.include("ResolutionDeadlinePassed");
+      }
+    });
+
+    it("lets anyone expire the event and bettors claim refunds", async () => {
+      // Called by the provider wallet, not a platform key
+      await program.methods
+        .expireEvent()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          bondVault: null,
+          proposerDoomAccount: null,
+          disputerDoomAccount: null,
+          tokenProgram: null,
+        })
+        .rpc();
+
+      const event = await program.account.predictionEvent.fetch(eventPDA);
+      expect(event.status).to.deep.equal({ expired: {} });
+
+      const before = Number((await getAccount(provider.connection, user2Life)).amount);
+
+      await program.methods
+        .claimRefund()
+        .accounts({
+          platformConfig: platformConfigPDA,
+          event: eventPDA,
+          userBet: userBetPDA,
+          userDoomAccount: user2Doom,
+          userLifeAccount: user2Life,
+          doomVault: doomVaultPDA,
+          lifeVault: lifeVaultPDA,
+          user: user2.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          eventOutcomes: null,
+        })
+        .signers([user2])
+        .rpc();
+
+      const after = Number((await getAccount(provider.connection, user2Life)).amount);
+      expect(after - before).to.equal(10 * 10 ** 9);
+    });
   });
 
   describe("edge cases", () => {
@@ -640,7 +5130,7 @@ describe("prediction-market", () => {
     it("prevents actions when platform is paused", async () => {
       // Pause platform
       await program.methods
-        .updatePlatform(null, null, true)
+        .updatePlatform(null, null, PAUSE_ALL, null, null, null)
         .accounts({
           platformConfig: platformConfigPDA,
           authority: authority.publicKey,
@@ -676,7 +5166,8 @@ describe("prediction-market", () => {
             "Paused Event",
             "Should fail",
             new anchor.BN(now + 86400),
-            new anchor.BN(now + 172800)
+            new anchor.BN(now + 172800),
+            null
           )
           .accounts({
             platformConfig: platformConfigPDA,
@@ -690,6 +5181,9 @@ describe("prediction-market", () => {
             systemProgram: SystemProgram.programId,
             tokenProgram: TOKEN_PROGRAM_ID,
             rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+            resolverAllowlist: null,
+            creatorDoomAccount: null,
+            bondVault: null,
           })
           .signers([user1])
           .rpc();
@@ -700,7 +5194,7 @@ describe("prediction-market", () => {
 
       // Unpause platform for subsequent tests
       await program.methods
-        .updatePlatform(null, null, false)
+        .updatePlatform(null, null, 0, null, null, null)
         .accounts({
           platformConfig: platformConfigPDA,
           authority: authority.publicKey,
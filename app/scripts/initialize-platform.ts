@@ -10,21 +10,29 @@ import {
   Transaction,
   TransactionInstruction,
   SystemProgram,
+  SYSVAR_RENT_PUBKEY,
   sendAndConfirmTransaction,
 } from '@solana/web3.js'
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token'
 import * as fs from 'fs'
 import * as path from 'path'
 
 const PROGRAM_ID = new PublicKey('BMmGykphijTgvB7WMim9UVqi9976iibKf6uYAiGXC7Mc')
 const RPC_URL = 'https://api.devnet.solana.com'
+const DOOM_MINT = new PublicKey(process.env.VITE_DOOM_TOKEN_MINT || '9Dc8sELJerfzPfk9DMP5vahLFxvr6rzn7PB8E6EK4Ah5')
+const LIFE_MINT = new PublicKey(process.env.VITE_LIFE_TOKEN_MINT || 'D2DDKv5JXjL1APVBP1ySY3PMUFEjL7R8NRz9r9a4JCvE')
 
 // Fee in basis points (200 = 2%)
 const FEE_BASIS_POINTS = 200
 
+// Seconds fee and oracle changes wait before they can be applied
+const CONFIG_DELAY = 24 * 60 * 60
+
 async function main() {
   console.log('Initializing Prediction Market Platform...')
   console.log('Program ID:', PROGRAM_ID.toString())
   console.log('Fee:', FEE_BASIS_POINTS / 100, '%')
+  console.log('Config delay:', CONFIG_DELAY, 's')
 
   // Load wallet keypair
   const walletPath = path.join(process.env.HOME || '', '.config/solana/id.json')
@@ -40,6 +48,16 @@ async function main() {
   console.log('Platform Config PDA:', platformConfig.toString())
   console.log('Bump:', bump)
 
+  const [bondVault] = PublicKey.findProgramAddressSync([Buffer.from('bond_vault')], PROGRAM_ID)
+  const [doomTreasury] = PublicKey.findProgramAddressSync(
+    [Buffer.from('treasury'), DOOM_MINT.toBuffer()],
+    PROGRAM_ID
+  )
+  const [lifeTreasury] = PublicKey.findProgramAddressSync(
+    [Buffer.from('treasury'), LIFE_MINT.toBuffer()],
+    PROGRAM_ID
+  )
+
   // Connect to devnet
   const connection = new Connection(RPC_URL, 'confirmed')
 
@@ -61,14 +79,25 @@ async function main() {
   const feeBuffer = Buffer.alloc(2)
   feeBuffer.writeUInt16LE(FEE_BASIS_POINTS)
 
-  const data = Buffer.concat([discriminator, feeBuffer])
+  // config_delay as i64 (little-endian)
+  const delayBuffer = Buffer.alloc(8)
+  delayBuffer.writeBigInt64LE(BigInt(CONFIG_DELAY))
+
+  const data = Buffer.concat([discriminator, feeBuffer, delayBuffer])
 
   // Build instruction
   const instruction = new TransactionInstruction({
     keys: [
       { pubkey: platformConfig, isSigner: false, isWritable: true },
+      { pubkey: DOOM_MINT, isSigner: false, isWritable: false },
+      { pubkey: LIFE_MINT, isSigner: false, isWritable: false },
+      { pubkey: bondVault, isSigner: false, isWritable: true },
+      { pubkey: doomTreasury, isSigner: false, isWritable: true },
+      { pubkey: lifeTreasury, isSigner: false, isWritable: true },
       { pubkey: authority.publicKey, isSigner: true, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
     ],
     programId: PROGRAM_ID,
     data,
@@ -3,27 +3,51 @@
 /**
  * Key Rotation Script
  *
- * Rotates encryption keys for sensitive data stored in the database.
+ * Rotates encryption keys for sensitive data stored in the database, and
+ * the prediction market's on-chain platform authority.
  * Should be run periodically (recommended: every 90 days) or after
  * any suspected key compromise.
  *
  * Usage:
  *   OLD_ENCRYPTION_KEY=<old> NEW_ENCRYPTION_KEY=<new> npx ts-node scripts/rotate-keys.ts
+ *   NEW_AUTHORITY_KEYPAIR_PATH=<path> npx ts-node scripts/rotate-keys.ts
+ *
+ * The authority is transferred in two steps: the current authority
+ * (AUTHORITY_KEYPAIR_PATH, default ~/.config/solana/id.json) proposes the new
+ * one with `propose_authority`, which then takes over with `accept_authority`.
  *
  * Prerequisites:
  * - Database access
  * - Both old and new encryption keys
+ * - For the authority, both the current and the new authority keypairs
  * - Run during low-traffic period
  */
 
 import 'dotenv/config'
+import * as fs from 'fs'
+import * as path from 'path'
 import { eq } from 'drizzle-orm'
+import {
+  Keypair,
+  PublicKey,
+  Transaction,
+  TransactionInstruction,
+  sendAndConfirmTransaction,
+} from '@solana/web3.js'
 import { db } from '../src/db'
 import { adminUsers } from '../src/db/schema'
 import { reEncrypt } from '../src/lib/encryption'
+import { getConnection, getPredictionMarketProgramId } from '../src/lib/solana/config'
 
 const OLD_KEY = process.env.OLD_ENCRYPTION_KEY
 const NEW_KEY = process.env.NEW_ENCRYPTION_KEY
+const AUTHORITY_KEYPAIR_PATH = process.env.AUTHORITY_KEYPAIR_PATH ||
+  path.join(process.env.HOME || '', '.config/solana/id.json')
+const NEW_AUTHORITY_KEYPAIR_PATH = process.env.NEW_AUTHORITY_KEYPAIR_PATH
+
+// First 8 bytes of sha256("global:<instruction>")
+const PROPOSE_AUTHORITY_DISCRIMINATOR = Buffer.from([20, 148, 236, 198, 76, 119, 99, 142])
+const ACCEPT_AUTHORITY_DISCRIMINATOR = Buffer.from([107, 86, 198, 91, 33, 12, 107, 160])
 
 interface RotationResult {
   table: string
@@ -95,6 +119,66 @@ async function rotateAdminSecrets(): Promise<RotationResult[]> {
   return results
 }
 
+function loadKeypair(keypairPath: string): Keypair {
+  const keypairData = JSON.parse(fs.readFileSync(keypairPath, 'utf-8'))
+  return Keypair.fromSecretKey(new Uint8Array(keypairData))
+}
+
+async function rotatePlatformAuthority(): Promise<RotationResult[]> {
+  console.log('Rotating platform authority...')
+
+  const programId = getPredictionMarketProgramId()
+  const [platformConfig] = PublicKey.findProgramAddressSync(
+    [Buffer.from('platform_config')],
+    programId
+  )
+  const result: RotationResult = {
+    table: 'platform_config',
+    field: 'authority',
+    recordId: platformConfig.toBase58(),
+    success: false,
+  }
+
+  try {
+    const connection = getConnection()
+    const authority = loadKeypair(AUTHORITY_KEYPAIR_PATH)
+    const newAuthority = loadKeypair(NEW_AUTHORITY_KEYPAIR_PATH!)
+
+    // The current authority proposes, and only the proposed key can accept
+    const propose = new TransactionInstruction({
+      keys: [
+        { pubkey: platformConfig, isSigner: false, isWritable: true },
+        { pubkey: authority.publicKey, isSigner: true, isWritable: false },
+      ],
+      programId,
+      data: Buffer.concat([PROPOSE_AUTHORITY_DISCRIMINATOR, newAuthority.publicKey.toBuffer()]),
+    })
+    await sendAndConfirmTransaction(connection, new Transaction().add(propose), [authority], {
+      commitment: 'confirmed',
+    })
+    console.log(`  Proposed ${newAuthority.publicKey.toBase58()}`)
+
+    const accept = new TransactionInstruction({
+      keys: [
+        { pubkey: platformConfig, isSigner: false, isWritable: true },
+        { pubkey: newAuthority.publicKey, isSigner: true, isWritable: false },
+      ],
+      programId,
+      data: ACCEPT_AUTHORITY_DISCRIMINATOR,
+    })
+    await sendAndConfirmTransaction(connection, new Transaction().add(accept), [newAuthority], {
+      commitment: 'confirmed',
+    })
+    console.log('  Accepted by the new authority')
+
+    result.success = true
+  } catch (error) {
+    result.error = error instanceof Error ? error.message : 'Unknown error'
+  }
+
+  return [result]
+}
+
 async function main(): Promise<void> {
   console.log('='.repeat(60))
   console.log('ENCRYPTION KEY ROTATION')
@@ -102,17 +186,23 @@ async function main(): Promise<void> {
   console.log()
 
   // Validate environment
-  if (!OLD_KEY) {
+  const rotateEncryption = Boolean(OLD_KEY || NEW_KEY)
+  if (!rotateEncryption && !NEW_AUTHORITY_KEYPAIR_PATH) {
+    console.error('ERROR: set OLD_ENCRYPTION_KEY and NEW_ENCRYPTION_KEY, or NEW_AUTHORITY_KEYPAIR_PATH')
+    process.exit(1)
+  }
+
+  if (rotateEncryption && !OLD_KEY) {
     console.error('ERROR: OLD_ENCRYPTION_KEY environment variable required')
     process.exit(1)
   }
 
-  if (!NEW_KEY) {
+  if (rotateEncryption && !NEW_KEY) {
     console.error('ERROR: NEW_ENCRYPTION_KEY environment variable required')
     process.exit(1)
   }
 
-  if (OLD_KEY === NEW_KEY) {
+  if (rotateEncryption && OLD_KEY === NEW_KEY) {
     console.error('ERROR: OLD and NEW keys must be different')
     process.exit(1)
   }
@@ -123,8 +213,16 @@ async function main(): Promise<void> {
   const allResults: RotationResult[] = []
 
   // Rotate admin secrets
-  const adminResults = await rotateAdminSecrets()
-  allResults.push(...adminResults)
+  if (rotateEncryption) {
+    const adminResults = await rotateAdminSecrets()
+    allResults.push(...adminResults)
+  }
+
+  // Rotate the on-chain platform authority
+  if (NEW_AUTHORITY_KEYPAIR_PATH) {
+    const authorityResults = await rotatePlatformAuthority()
+    allResults.push(...authorityResults)
+  }
 
   // Add more rotation functions here as needed
   // const userResults = await rotateUserSecrets()
@@ -156,10 +254,17 @@ async function main(): Promise<void> {
   console.log('Key rotation completed successfully!')
   console.log()
   console.log('NEXT STEPS:')
-  console.log('1. Update ENCRYPTION_KEY in production environment')
-  console.log('2. Restart all application instances')
-  console.log('3. Verify encryption/decryption still works')
-  console.log('4. Securely delete the old encryption key')
+  if (rotateEncryption) {
+    console.log('- Update ENCRYPTION_KEY in production environment')
+    console.log('- Restart all application instances')
+    console.log('- Verify encryption/decryption still works')
+    console.log('- Securely delete the old encryption key')
+  }
+  if (NEW_AUTHORITY_KEYPAIR_PATH) {
+    console.log('- Reassign the pauser, fee manager and moderator roles with set_roles if they used the old key')
+    console.log('- Queue a new oracle with update_platform if it used the old key')
+    console.log('- Securely delete the old authority keypair')
+  }
   console.log()
 }
 
@@ -13,17 +13,21 @@ import {
   SystemProgram,
   SYSVAR_RENT_PUBKEY,
 } from '@solana/web3.js'
-import { BN } from '@coral-xyz/anchor'
+import { BN, utils } from '@coral-xyz/anchor'
 import {
   TOKEN_PROGRAM_ID,
   getAssociatedTokenAddressSync,
 } from '@solana/spl-token'
 import { getNetworkConfig, getProgramId } from '../config'
 
-// Outcome constants matching the on-chain program
+// Outcome constants matching the on-chain program; Categorical and Scalar carry a value
 export const Outcome = {
   Doom: 0,
   Life: 1,
+  Categorical: 2,
+  Scalar: 3,
+  Invalid: 4,
+  Draw: 5,
 } as const
 export type Outcome = (typeof Outcome)[keyof typeof Outcome]
 
@@ -32,19 +36,93 @@ export const EventStatus = {
   Active: 0,
   Resolved: 1,
   Cancelled: 2,
+  Expired: 3,
+  Closed: 4,
+  Settled: 5,
+  Proposed: 6,
+  Disputed: 7,
+  /** Closed with bets or oracle votes still open, until they are closed */
+  Swept: 8,
 } as const
 export type EventStatus = (typeof EventStatus)[keyof typeof EventStatus]
 
+// Event kind matching the on-chain program
+export const EventKind = {
+  Binary: 0,
+  Categorical: 1,
+  Scalar: 2,
+} as const
+export type EventKind = (typeof EventKind)[keyof typeof EventKind]
+
+// Pricing mode matching the on-chain program
+export const PricingMode = {
+  Parimutuel: 0,
+  Lmsr: 1,
+} as const
+export type PricingMode = (typeof PricingMode)[keyof typeof PricingMode]
+
+// Comparison of an oracle price against the event's threshold
+export const PriceComparator = {
+  Below: 0,
+  Above: 1,
+} as const
+export type PriceComparator = (typeof PriceComparator)[keyof typeof PriceComparator]
+
+// Account discriminators
+const EVENT_DISCRIMINATOR = Buffer.from([88, 209, 255, 99, 200, 19, 183, 221])
+const USER_BET_DISCRIMINATOR = Buffer.from([180, 131, 8, 241, 60, 243, 46, 63])
+
+// Pause flags, combined in PlatformConfig.paused
+export const PauseFlags = {
+  Creation: 1 << 0,
+  Betting: 1 << 1,
+  Resolution: 1 << 2,
+  Claims: 1 << 3,
+  Withdrawals: 1 << 4,
+} as const
+
 // Account types
+/** Bet size limits; 0 means no limit except for minBet */
+export interface BetLimits {
+  minBet: BN
+  maxBet: BN
+  maxUserStake: BN
+  maxSideShareBps: number
+}
+
 export interface PlatformConfig {
   authority: PublicKey
+  pendingAuthority: PublicKey | null
+  pauser: PublicKey
+  feeManager: PublicKey
+  moderator: PublicKey
   oracle: PublicKey
+  doomMint: PublicKey
+  lifeMint: PublicKey
   feeBasisPoints: number
-  paused: boolean
+  configDelay: BN
+  pendingFeeBasisPoints: number | null
+  pendingOracle: PublicKey | null
+  pendingConfigDelay: BN | null
+  pendingCreatorFeeBps: number | null
+  pendingConfigEffectiveAt: BN
+  /** Bitset of PauseFlags */
+  paused: number
   totalDoomFees: BN
   totalLifeFees: BN
   totalEvents: BN
   totalBets: BN
+  disputeWindow: BN
+  disputeBond: BN
+  creationBond: BN
+  creatorFeeBps: number
+  betLimits: BetLimits
+  /** Seconds before the deadline that betting closes on new events */
+  bettingCutoff: BN
+  /** Share of a withdrawn stake kept in the pool, in basis points */
+  exitPenaltyBps: number
+  /** Seconds after the resolution deadline that new events can be swept */
+  sweepWindow: BN
   bump: number
 }
 
@@ -54,27 +132,74 @@ export interface PredictionEvent {
   title: string
   description: string
   deadline: BN
+  bettingClosesAt: BN
   resolutionDeadline: BN
+  resolver: PublicKey | null
+  feeBasisPoints: number
+  /** Creators' share of the fee, snapshotted at creation */
+  creatorFeeBps: number
+  betLimits: BetLimits
+  exitPenaltyBps: number
+  sweepWindow: BN
+  paused: boolean
   status: EventStatus
+  kind: EventKind
+  outcomeCount: number
+  lowerBound: BN
+  upperBound: BN
+  priceFeed: PublicKey | null
+  priceThreshold: BN
+  priceExpo: number
+  priceComparator: PriceComparator
   outcome: Outcome | null
+  /** Categorical index or scalar value of the outcome */
+  outcomeValue: BN | null
   doomPool: BN
   lifePool: BN
-  totalBettors: BN
+  doomPenaltyPool: BN
+  lifePenaltyPool: BN
+  totalBettors: number
+  outstandingBets: number
   createdAt: BN
   resolvedAt: BN | null
+  proposer: PublicKey
+  disputer: PublicKey | null
+  disputeEndsAt: BN
+  bond: BN
+  committeeOutcome: Outcome | null
+  committeeOutcomeValue: BN | null
+  committeeVotes: number
+  /** Oracle vote accounts not yet closed */
+  oracleVotes: number
+  settledStake: BN
+  creationBond: BN
+  creatorDoomFees: BN
+  creatorLifeFees: BN
+  tokenized: boolean
+  pricing: PricingMode
+  lmsrLiquidity: BN
+  lmsrCollected: BN
   doomVaultBump: number
   lifeVaultBump: number
+  doomShareMintBump: number
+  lifeShareMintBump: number
   bump: number
 }
 
 export interface UserBet {
   event: PublicKey
   user: PublicKey
-  outcome: Outcome
-  amount: BN
+  doomAmount: BN
+  lifeAmount: BN
+  /** Stake per outcome on categorical events */
+  outcomeAmounts: BN[]
+  /** DOOM paid for shares on LMSR events */
+  lmsrCost: BN
   placedAt: BN
   claimed: boolean
   refunded: boolean
+  /** Lost on a resolved event and counted in the user's stats */
+  settledLoss: boolean
   bump: number
 }
 
@@ -103,6 +228,11 @@ const USER_BET_SEED = 'user_bet'
 const USER_STATS_SEED = 'user_stats'
 const DOOM_VAULT_SEED = 'vault_doom'
 const LIFE_VAULT_SEED = 'vault_life'
+const RESOLVER_ALLOWLIST_SEED = 'resolver_allowlist'
+const BOND_VAULT_SEED = 'bond_vault'
+const TREASURY_SEED = 'treasury'
+const DOOM_SHARE_MINT_SEED = 'share_doom'
+const LIFE_SHARE_MINT_SEED = 'share_life'
 
 /**
  * Get the program ID from config
@@ -152,6 +282,36 @@ export function findUserStatsPDA(user: PublicKey): [PublicKey, number] {
   )
 }
 
+/**
+ * Derive the resolver allowlist PDA
+ */
+export function findResolverAllowlistPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(RESOLVER_ALLOWLIST_SEED)],
+    getPredictionMarketProgramId()
+  )
+}
+
+/**
+ * Derive the platform bond vault PDA
+ */
+export function findBondVaultPDA(): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(BOND_VAULT_SEED)],
+    getPredictionMarketProgramId()
+  )
+}
+
+/**
+ * Derive the platform fee treasury PDA for a mint
+ */
+export function findTreasuryPDA(mint: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(TREASURY_SEED), mint.toBuffer()],
+    getPredictionMarketProgramId()
+  )
+}
+
 /**
  * Derive the DOOM vault PDA for an event
  */
@@ -172,15 +332,33 @@ export function findLifeVaultPDA(event: PublicKey): [PublicKey, number] {
   )
 }
 
+/**
+ * Derive the share mint PDA of one side of a tokenized event
+ */
+export function findShareMintPDA(
+  event: PublicKey,
+  outcome: typeof Outcome.Doom | typeof Outcome.Life
+): [PublicKey, number] {
+  const seed = outcome === Outcome.Doom ? DOOM_SHARE_MINT_SEED : LIFE_SHARE_MINT_SEED
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from(seed), event.toBuffer()],
+    getPredictionMarketProgramId()
+  )
+}
+
 /**
  * Build a place bet transaction
+ *
+ * On LMSR-priced events `amount` is a number of shares and `maxCost` caps
+ * the DOOM paid for them.
  */
 export async function buildPlaceBetTransaction(
   connection: Connection,
   user: PublicKey,
   eventId: number | BN,
   outcome: Outcome,
-  amount: BN
+  amount: BN,
+  maxCost?: BN
 ): Promise<Transaction> {
   const [platformConfig] = findPlatformConfigPDA()
   const [event] = findEventPDA(eventId)
@@ -197,11 +375,14 @@ export async function buildPlaceBetTransaction(
   const userLifeAccount = getAssociatedTokenAddressSync(lifeMint, user)
 
   // Build instruction data
-  // place_bet discriminator + outcome (1 byte) + amount (8 bytes)
+  // place_bet discriminator + outcome (1 byte) + amount (8 bytes) + max_cost (Option<u64>)
   const discriminator = Buffer.from([226, 19, 18, 237, 130, 0, 29, 76]) // place_bet
   const outcomeBuffer = Buffer.from([outcome])
   const amountBuffer = amount.toArrayLike(Buffer, 'le', 8)
-  const data = Buffer.concat([discriminator, outcomeBuffer, amountBuffer])
+  const maxCostBuffer = maxCost
+    ? Buffer.concat([Buffer.from([1]), maxCost.toArrayLike(Buffer, 'le', 8)])
+    : Buffer.from([0])
+  const data = Buffer.concat([discriminator, outcomeBuffer, amountBuffer, maxCostBuffer])
 
   const programId = getPredictionMarketProgramId()
 
@@ -218,6 +399,12 @@ export async function buildPlaceBetTransaction(
       { pubkey: user, isSigner: true, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      // Categorical event accounts, unused for binary events
+      { pubkey: programId, isSigner: false, isWritable: false },
+      { pubkey: programId, isSigner: false, isWritable: false },
+      // Share token accounts, unused unless the event is tokenized
+      { pubkey: programId, isSigner: false, isWritable: false },
+      { pubkey: programId, isSigner: false, isWritable: false },
     ],
     programId,
     data,
@@ -237,9 +424,7 @@ export async function buildPlaceBetTransaction(
 export async function buildClaimWinningsTransaction(
   connection: Connection,
   user: PublicKey,
-  eventId: number | BN,
-  doomFeeAccount: PublicKey,
-  lifeFeeAccount: PublicKey
+  eventId: number | BN
 ): Promise<Transaction> {
   const [platformConfig] = findPlatformConfigPDA()
   const [event] = findEventPDA(eventId)
@@ -263,17 +448,19 @@ export async function buildClaimWinningsTransaction(
   const instruction = new TransactionInstruction({
     keys: [
       { pubkey: platformConfig, isSigner: false, isWritable: true },
-      { pubkey: event, isSigner: false, isWritable: false },
+      { pubkey: event, isSigner: false, isWritable: true },
       { pubkey: userBet, isSigner: false, isWritable: true },
       { pubkey: userDoomAccount, isSigner: false, isWritable: true },
       { pubkey: userLifeAccount, isSigner: false, isWritable: true },
       { pubkey: doomVault, isSigner: false, isWritable: true },
       { pubkey: lifeVault, isSigner: false, isWritable: true },
-      { pubkey: doomFeeAccount, isSigner: false, isWritable: true },
-      { pubkey: lifeFeeAccount, isSigner: false, isWritable: true },
+      { pubkey: findTreasuryPDA(doomMint)[0], isSigner: false, isWritable: true },
+      { pubkey: findTreasuryPDA(lifeMint)[0], isSigner: false, isWritable: true },
       { pubkey: userStats, isSigner: false, isWritable: true },
       { pubkey: user, isSigner: true, isWritable: true },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      // Categorical event accounts, unused for binary events
+      { pubkey: programId, isSigner: false, isWritable: false },
     ],
     programId,
     data: discriminator,
@@ -287,6 +474,129 @@ export async function buildClaimWinningsTransaction(
   return transaction
 }
 
+/**
+ * Build a claim refund transaction
+ *
+ * Returns the full stake on cancelled, expired or Invalid events.
+ */
+export async function buildClaimRefundTransaction(
+  connection: Connection,
+  user: PublicKey,
+  eventId: number | BN
+): Promise<Transaction> {
+  const [platformConfig] = findPlatformConfigPDA()
+  const [event] = findEventPDA(eventId)
+  const [userBet] = findUserBetPDA(event, user)
+  const [doomVault] = findDoomVaultPDA(event)
+  const [lifeVault] = findLifeVaultPDA(event)
+
+  const config = getNetworkConfig()
+  const doomMint = new PublicKey(config.tokens.doom.mint)
+  const lifeMint = new PublicKey(config.tokens.life.mint)
+
+  const userDoomAccount = getAssociatedTokenAddressSync(doomMint, user)
+  const userLifeAccount = getAssociatedTokenAddressSync(lifeMint, user)
+
+  // claim_refund discriminator
+  const discriminator = Buffer.from([15, 16, 30, 161, 255, 228, 97, 60])
+
+  const programId = getPredictionMarketProgramId()
+
+  const instruction = new TransactionInstruction({
+    keys: [
+      { pubkey: platformConfig, isSigner: false, isWritable: false },
+      { pubkey: event, isSigner: false, isWritable: true },
+      { pubkey: userBet, isSigner: false, isWritable: true },
+      { pubkey: userDoomAccount, isSigner: false, isWritable: true },
+      { pubkey: userLifeAccount, isSigner: false, isWritable: true },
+      { pubkey: doomVault, isSigner: false, isWritable: true },
+      { pubkey: lifeVault, isSigner: false, isWritable: true },
+      { pubkey: user, isSigner: true, isWritable: true },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      // Categorical event accounts, unused for binary events
+      { pubkey: programId, isSigner: false, isWritable: false },
+    ],
+    programId,
+    data: discriminator,
+  })
+
+  const transaction = new Transaction().add(instruction)
+  transaction.feePayer = user
+  const latestBlockhash = await connection.getLatestBlockhash()
+  transaction.recentBlockhash = latestBlockhash.blockhash
+
+  return transaction
+}
+
+/**
+ * Build a redeem shares transaction
+ *
+ * Tokenized events pay out through their shares rather than `claim_winnings`;
+ * each entry burns `amount` of the holder's shares of one side.
+ */
+export async function buildRedeemSharesTransaction(
+  connection: Connection,
+  holder: PublicKey,
+  eventId: number | BN,
+  shares: { outcome: typeof Outcome.Doom | typeof Outcome.Life; amount: BN }[]
+): Promise<Transaction> {
+  const [platformConfig] = findPlatformConfigPDA()
+  const [event] = findEventPDA(eventId)
+  const [doomVault] = findDoomVaultPDA(event)
+  const [lifeVault] = findLifeVaultPDA(event)
+
+  const config = getNetworkConfig()
+  const doomMint = new PublicKey(config.tokens.doom.mint)
+  const lifeMint = new PublicKey(config.tokens.life.mint)
+
+  const holderDoomAccount = getAssociatedTokenAddressSync(doomMint, holder)
+  const holderLifeAccount = getAssociatedTokenAddressSync(lifeMint, holder)
+
+  // redeem_shares discriminator
+  const discriminator = Buffer.from([239, 154, 224, 89, 240, 196, 42, 187])
+
+  const programId = getPredictionMarketProgramId()
+  const transaction = new Transaction()
+
+  for (const { outcome, amount } of shares) {
+    const [shareMint] = findShareMintPDA(event, outcome)
+    const holderShareAccount = getAssociatedTokenAddressSync(shareMint, holder)
+    // redeem_shares discriminator + outcome (1 byte) + amount (8 bytes)
+    const data = Buffer.concat([
+      discriminator,
+      Buffer.from([outcome]),
+      amount.toArrayLike(Buffer, 'le', 8),
+    ])
+
+    transaction.add(
+      new TransactionInstruction({
+        keys: [
+          { pubkey: platformConfig, isSigner: false, isWritable: true },
+          { pubkey: event, isSigner: false, isWritable: true },
+          { pubkey: shareMint, isSigner: false, isWritable: true },
+          { pubkey: holderShareAccount, isSigner: false, isWritable: true },
+          { pubkey: holderDoomAccount, isSigner: false, isWritable: true },
+          { pubkey: holderLifeAccount, isSigner: false, isWritable: true },
+          { pubkey: doomVault, isSigner: false, isWritable: true },
+          { pubkey: lifeVault, isSigner: false, isWritable: true },
+          { pubkey: findTreasuryPDA(doomMint)[0], isSigner: false, isWritable: true },
+          { pubkey: findTreasuryPDA(lifeMint)[0], isSigner: false, isWritable: true },
+          { pubkey: holder, isSigner: true, isWritable: false },
+          { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ],
+        programId,
+        data,
+      })
+    )
+  }
+
+  transaction.feePayer = holder
+  const latestBlockhash = await connection.getLatestBlockhash()
+  transaction.recentBlockhash = latestBlockhash.blockhash
+
+  return transaction
+}
+
 /**
  * Build a create event transaction
  */
@@ -297,7 +607,8 @@ export async function buildCreateEventTransaction(
   title: string,
   description: string,
   deadline: number,
-  resolutionDeadline: number
+  resolutionDeadline: number,
+  resolver?: PublicKey
 ): Promise<Transaction> {
   const [platformConfig] = findPlatformConfigPDA()
   const [event] = findEventPDA(eventId)
@@ -309,9 +620,13 @@ export async function buildCreateEventTransaction(
   const doomMint = new PublicKey(config.tokens.doom.mint)
   const lifeMint = new PublicKey(config.tokens.life.mint)
 
+  // Creation bond is escrowed from the creator's DOOM account
+  const creatorDoomAccount = getAssociatedTokenAddressSync(doomMint, creator)
+  const [bondVault] = findBondVaultPDA()
+
   const id = typeof eventId === 'number' ? new BN(eventId) : eventId
 
-  // create_event discriminator + event_id + title + description + deadline + resolution_deadline
+  // create_event discriminator + event_id + title + description + deadline + resolution_deadline + resolver
   const discriminator = Buffer.from([49, 43, 48, 5, 83, 65, 158, 226])
   const eventIdBuffer = id.toArrayLike(Buffer, 'le', 8)
   const titleBuffer = Buffer.from(title)
@@ -322,6 +637,9 @@ export async function buildCreateEventTransaction(
   descLenBuffer.writeUInt32LE(descBuffer.length)
   const deadlineBuffer = new BN(deadline).toArrayLike(Buffer, 'le', 8)
   const resDeadlineBuffer = new BN(resolutionDeadline).toArrayLike(Buffer, 'le', 8)
+  const resolverBuffer = resolver
+    ? Buffer.concat([Buffer.from([1]), resolver.toBuffer()])
+    : Buffer.from([0])
 
   const data = Buffer.concat([
     discriminator,
@@ -332,6 +650,7 @@ export async function buildCreateEventTransaction(
     descBuffer,
     deadlineBuffer,
     resDeadlineBuffer,
+    resolverBuffer,
   ])
 
   const programId = getPredictionMarketProgramId()
@@ -349,6 +668,10 @@ export async function buildCreateEventTransaction(
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
       { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+      // Resolver allowlist, only needed when binding a resolver
+      { pubkey: resolver ? findResolverAllowlistPDA()[0] : programId, isSigner: false, isWritable: false },
+      { pubkey: creatorDoomAccount, isSigner: false, isWritable: true },
+      { pubkey: bondVault, isSigner: false, isWritable: true },
     ],
     programId,
     data,
@@ -447,7 +770,7 @@ export async function fetchAllEvents(
   // Get all program accounts with event discriminator
   const accounts = await connection.getProgramAccounts(programId, {
     filters: [
-      { dataSize: 800 }, // Approximate event account size
+      { memcmp: { offset: 0, bytes: utils.bytes.bs58.encode(EVENT_DISCRIMINATOR) } },
     ],
   })
 
@@ -479,6 +802,7 @@ export async function fetchUserBets(
 
   const accounts = await connection.getProgramAccounts(programId, {
     filters: [
+      { memcmp: { offset: 0, bytes: utils.bytes.bs58.encode(USER_BET_DISCRIMINATOR) } },
       { memcmp: { offset: 8 + 32, bytes: user.toBase58() } }, // user field after event
     ],
   })
@@ -504,102 +828,234 @@ export async function fetchUserBets(
 function parsePlatformConfig(data: Buffer): PlatformConfig {
   let offset = 0
 
-  const authority = new PublicKey(data.slice(offset, offset + 32))
-  offset += 32
-
-  const oracle = new PublicKey(data.slice(offset, offset + 32))
-  offset += 32
-
-  const feeBasisPoints = data.readUInt16LE(offset)
-  offset += 2
+  const readPubkey = () => {
+    const key = new PublicKey(data.slice(offset, offset + 32))
+    offset += 32
+    return key
+  }
+  const readU64 = () => {
+    const value = new BN(data.slice(offset, offset + 8), 'le')
+    offset += 8
+    return value
+  }
+  const readU16 = () => {
+    const value = data.readUInt16LE(offset)
+    offset += 2
+    return value
+  }
+  // Borsh options are a tag byte followed by the value only when present
+  const readOption = <T>(read: () => T): T | null => {
+    const present = data[offset] === 1
+    offset += 1
+    return present ? read() : null
+  }
 
-  const paused = data[offset] === 1
+  const authority = readPubkey()
+  const pendingAuthority = readOption(readPubkey)
+  const pauser = readPubkey()
+  const feeManager = readPubkey()
+  const moderator = readPubkey()
+  const oracle = readPubkey()
+  const doomMint = readPubkey()
+  const lifeMint = readPubkey()
+  const feeBasisPoints = readU16()
+  const configDelay = readU64()
+  const pendingFeeBasisPoints = readOption(readU16)
+  const pendingOracle = readOption(readPubkey)
+  const pendingConfigDelay = readOption(readU64)
+  const pendingCreatorFeeBps = readOption(readU16)
+  const pendingConfigEffectiveAt = readU64()
+
+  const paused = data[offset]
   offset += 1
 
-  const totalDoomFees = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const totalLifeFees = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const totalEvents = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const totalBets = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
+  const totalDoomFees = readU64()
+  const totalLifeFees = readU64()
+  const totalEvents = readU64()
+  const totalBets = readU64()
+  const disputeWindow = readU64()
+  const disputeBond = readU64()
+  const creationBond = readU64()
+  const creatorFeeBps = readU16()
+  const betLimits = {
+    minBet: readU64(),
+    maxBet: readU64(),
+    maxUserStake: readU64(),
+    maxSideShareBps: readU16(),
+  }
+  const bettingCutoff = readU64()
+  const exitPenaltyBps = readU16()
+  const sweepWindow = readU64()
 
+  // Skip bond vault and treasury bumps
+  offset += 3
   const bump = data[offset]
 
   return {
     authority,
+    pendingAuthority,
+    pauser,
+    feeManager,
+    moderator,
     oracle,
+    doomMint,
+    lifeMint,
     feeBasisPoints,
+    configDelay,
+    pendingFeeBasisPoints,
+    pendingOracle,
+    pendingConfigDelay,
+    pendingCreatorFeeBps,
+    pendingConfigEffectiveAt,
     paused,
     totalDoomFees,
     totalLifeFees,
     totalEvents,
     totalBets,
+    disputeWindow,
+    disputeBond,
+    creationBond,
+    creatorFeeBps,
+    betLimits,
+    bettingCutoff,
+    exitPenaltyBps,
+    sweepWindow,
     bump,
   }
 }
 
-function parseEvent(data: Buffer): PredictionEvent {
+// Borsh readers over an account body with the discriminator stripped
+function createReader(data: Buffer) {
   let offset = 0
 
-  const eventId = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const creator = new PublicKey(data.slice(offset, offset + 32))
-  offset += 32
-
-  const titleLen = data.readUInt32LE(offset)
-  offset += 4
-  const title = data.slice(offset, offset + titleLen).toString()
-  offset += 128 // Fixed size allocation
-
-  const descLen = data.readUInt32LE(offset)
-  offset += 4
-  const description = data.slice(offset, offset + descLen).toString()
-  offset += 512 // Fixed size allocation
-
-  const deadline = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const resolutionDeadline = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const status = data[offset] as EventStatus
-  offset += 1
-
-  const hasOutcome = data[offset] === 1
-  offset += 1
-  const outcome = hasOutcome ? (data[offset] as Outcome) : null
-  offset += 1
-
-  const doomPool = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const lifePool = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const totalBettors = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const createdAt = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const hasResolvedAt = data[offset] === 1
-  offset += 1
-  const resolvedAt = hasResolvedAt ? new BN(data.slice(offset, offset + 8), 'le') : null
-  offset += 8
-
-  const doomVaultBump = data[offset]
-  offset += 1
+  const readU8 = () => {
+    const value = data[offset]
+    offset += 1
+    return value
+  }
+  const readBool = () => readU8() === 1
+  const readU16 = () => {
+    const value = data.readUInt16LE(offset)
+    offset += 2
+    return value
+  }
+  const readU32 = () => {
+    const value = data.readUInt32LE(offset)
+    offset += 4
+    return value
+  }
+  const readI32 = () => {
+    const value = data.readInt32LE(offset)
+    offset += 4
+    return value
+  }
+  const readU64 = () => {
+    const value = new BN(data.slice(offset, offset + 8), 'le')
+    offset += 8
+    return value
+  }
+  const readI64 = () => readU64().fromTwos(64)
+  const readPubkey = () => {
+    const key = new PublicKey(data.slice(offset, offset + 32))
+    offset += 32
+    return key
+  }
+  // Borsh strings are length-prefixed, not padded to their max length
+  const readString = () => {
+    const len = readU32()
+    const value = data.slice(offset, offset + len).toString()
+    offset += len
+    return value
+  }
+  const readVec = <T>(read: () => T): T[] => {
+    const len = readU32()
+    return Array.from({ length: len }, read)
+  }
+  const readOption = <T>(read: () => T): T | null => (readBool() ? read() : null)
+  // Outcome variants carry a categorical index or a scalar value
+  const readOutcome = (): { outcome: Outcome; value: BN | null } => {
+    const outcome = readU8() as Outcome
+    if (outcome === Outcome.Categorical) return { outcome, value: new BN(readU8()) }
+    if (outcome === Outcome.Scalar) return { outcome, value: readI64() }
+    return { outcome, value: null }
+  }
 
-  const lifeVaultBump = data[offset]
-  offset += 1
+  return {
+    readU8,
+    readBool,
+    readU16,
+    readU32,
+    readI32,
+    readU64,
+    readI64,
+    readPubkey,
+    readString,
+    readVec,
+    readOption,
+    readOutcome,
+  }
+}
 
-  const bump = data[offset]
+function parseEvent(data: Buffer): PredictionEvent {
+  const r = createReader(data)
+
+  const eventId = r.readU64()
+  const creator = r.readPubkey()
+  const title = r.readString()
+  const description = r.readString()
+  const deadline = r.readI64()
+  const bettingClosesAt = r.readI64()
+  const resolutionDeadline = r.readI64()
+  const resolver = r.readOption(r.readPubkey)
+  const feeBasisPoints = r.readU16()
+  const creatorFeeBps = r.readU16()
+  const betLimits = {
+    minBet: r.readU64(),
+    maxBet: r.readU64(),
+    maxUserStake: r.readU64(),
+    maxSideShareBps: r.readU16(),
+  }
+  const exitPenaltyBps = r.readU16()
+  const sweepWindow = r.readI64()
+  const paused = r.readBool()
+  const status = r.readU8() as EventStatus
+  const kind = r.readU8() as EventKind
+  const outcomeCount = r.readU8()
+  const lowerBound = r.readI64()
+  const upperBound = r.readI64()
+  const priceFeed = r.readOption(r.readPubkey)
+  const priceThreshold = r.readI64()
+  const priceExpo = r.readI32()
+  const priceComparator = r.readU8() as PriceComparator
+  const outcome = r.readOption(r.readOutcome)
+  const doomPool = r.readU64()
+  const lifePool = r.readU64()
+  const doomPenaltyPool = r.readU64()
+  const lifePenaltyPool = r.readU64()
+  const totalBettors = r.readU32()
+  const outstandingBets = r.readU32()
+  const createdAt = r.readI64()
+  const resolvedAt = r.readOption(r.readI64)
+  const proposer = r.readPubkey()
+  const disputer = r.readOption(r.readPubkey)
+  const disputeEndsAt = r.readI64()
+  const bond = r.readU64()
+  const committeeOutcome = r.readOption(r.readOutcome)
+  const committeeVotes = r.readU8()
+  const oracleVotes = r.readU8()
+  const settledStake = r.readU64()
+  const creationBond = r.readU64()
+  const creatorDoomFees = r.readU64()
+  const creatorLifeFees = r.readU64()
+  const tokenized = r.readBool()
+  const pricing = r.readU8() as PricingMode
+  const lmsrLiquidity = r.readU64()
+  const lmsrCollected = r.readU64()
+  const doomVaultBump = r.readU8()
+  const lifeVaultBump = r.readU8()
+  const doomShareMintBump = r.readU8()
+  const lifeShareMintBump = r.readU8()
+  const bump = r.readU8()
 
   return {
     eventId,
@@ -607,108 +1063,106 @@ function parseEvent(data: Buffer): PredictionEvent {
     title,
     description,
     deadline,
+    bettingClosesAt,
     resolutionDeadline,
+    resolver,
+    feeBasisPoints,
+    creatorFeeBps,
+    betLimits,
+    exitPenaltyBps,
+    sweepWindow,
+    paused,
     status,
-    outcome,
+    kind,
+    outcomeCount,
+    lowerBound,
+    upperBound,
+    priceFeed,
+    priceThreshold,
+    priceExpo,
+    priceComparator,
+    outcome: outcome?.outcome ?? null,
+    outcomeValue: outcome?.value ?? null,
     doomPool,
     lifePool,
+    doomPenaltyPool,
+    lifePenaltyPool,
     totalBettors,
+    outstandingBets,
     createdAt,
     resolvedAt,
+    proposer,
+    disputer,
+    disputeEndsAt,
+    bond,
+    committeeOutcome: committeeOutcome?.outcome ?? null,
+    committeeOutcomeValue: committeeOutcome?.value ?? null,
+    committeeVotes,
+    oracleVotes,
+    settledStake,
+    creationBond,
+    creatorDoomFees,
+    creatorLifeFees,
+    tokenized,
+    pricing,
+    lmsrLiquidity,
+    lmsrCollected,
     doomVaultBump,
     lifeVaultBump,
+    doomShareMintBump,
+    lifeShareMintBump,
     bump,
   }
 }
 
 function parseUserBet(data: Buffer): UserBet {
-  let offset = 0
-
-  const event = new PublicKey(data.slice(offset, offset + 32))
-  offset += 32
-
-  const user = new PublicKey(data.slice(offset, offset + 32))
-  offset += 32
-
-  const outcome = data[offset] as Outcome
-  offset += 1
-
-  const amount = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const placedAt = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const claimed = data[offset] === 1
-  offset += 1
-
-  const refunded = data[offset] === 1
-  offset += 1
-
-  const bump = data[offset]
+  const r = createReader(data)
+
+  const event = r.readPubkey()
+  const user = r.readPubkey()
+  const doomAmount = r.readU64()
+  const lifeAmount = r.readU64()
+  const outcomeAmounts = r.readVec(r.readU64)
+  const lmsrCost = r.readU64()
+  const placedAt = r.readI64()
+  const claimed = r.readBool()
+  const refunded = r.readBool()
+  const settledLoss = r.readBool()
+  const bump = r.readU8()
 
   return {
     event,
     user,
-    outcome,
-    amount,
+    doomAmount,
+    lifeAmount,
+    outcomeAmounts,
+    lmsrCost,
     placedAt,
     claimed,
     refunded,
+    settledLoss,
     bump,
   }
 }
 
 function parseUserStats(data: Buffer): UserStats {
-  let offset = 0
-
-  const user = new PublicKey(data.slice(offset, offset + 32))
-  offset += 32
-
-  const totalBets = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const wins = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const losses = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const totalWagered = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const totalWon = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const totalLost = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const netProfit = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const eventsCreated = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const hasFirstBet = data[offset] === 1
-  offset += 1
-  const firstBetAt = hasFirstBet ? new BN(data.slice(offset, offset + 8), 'le') : null
-  offset += 8
-
-  const hasLastBet = data[offset] === 1
-  offset += 1
-  const lastBetAt = hasLastBet ? new BN(data.slice(offset, offset + 8), 'le') : null
-  offset += 8
-
-  const currentStreak = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const bestStreak = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const worstStreak = new BN(data.slice(offset, offset + 8), 'le')
-  offset += 8
-
-  const bump = data[offset]
+  const r = createReader(data)
+
+  const user = r.readPubkey()
+  const totalBets = r.readU64()
+  const wins = r.readU64()
+  const losses = r.readU64()
+  const totalWagered = r.readU64()
+  const totalWon = r.readU64()
+  const totalLost = r.readU64()
+  const netProfit = r.readI64()
+  const eventsCreated = r.readU64()
+  const firstBetAt = r.readOption(r.readI64)
+  const lastBetAt = r.readOption(r.readI64)
+  const currentStreak = r.readI64()
+  const bestStreak = r.readI64()
+  const worstStreak = r.readI64()
+  const bump = r.readU8()
 
   return {
     user,
@@ -729,6 +1183,15 @@ function parseUserStats(data: Buffer): UserStats {
   }
 }
 
+/**
+ * Side of a binary position holding most of its stake
+ */
+export function betSide(bet: UserBet): { outcome: Outcome; amount: BN } {
+  return bet.doomAmount.gte(bet.lifeAmount)
+    ? { outcome: Outcome.Doom, amount: bet.doomAmount }
+    : { outcome: Outcome.Life, amount: bet.lifeAmount }
+}
+
 /**
  * Calculate estimated payout for a bet
  */
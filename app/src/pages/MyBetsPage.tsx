@@ -108,7 +108,12 @@ export function MyBetsPage() {
           outcome: bet.outcome,
           amount: bet.amount,
           placedAt: bet.placedAt,
-          status: bet.canClaim ? 'won' : bet.claimed ? 'won' : 'pending',
+          status:
+            event?.status === 'refund'
+              ? 'cancelled'
+              : bet.canClaim || bet.claimed
+                ? 'won'
+                : 'pending',
           estimatedPayout: bet.estimatedPayout,
           canClaim: bet.canClaim,
           claimed: bet.claimed,
@@ -14,7 +14,8 @@ import { create } from 'zustand'
 import { persist } from 'zustand/middleware'
 import { Connection, PublicKey, Transaction } from '@solana/web3.js'
 import { BN } from '@coral-xyz/anchor'
-import type { PredictionEvent, EventCategory, ID, Bet } from '@/types'
+import { getAssociatedTokenAddressSync } from '@solana/spl-token'
+import type { PredictionEvent, EventCategory, EventStatus, ID, Bet } from '@/types'
 import {
   fetchAllEvents,
   fetchEvent,
@@ -22,13 +23,16 @@ import {
   buildPlaceBetTransaction,
   buildCreateEventTransaction,
   buildClaimWinningsTransaction,
+  buildClaimRefundTransaction,
+  buildRedeemSharesTransaction,
   findEventPDA,
+  findShareMintPDA,
   Outcome,
   EventStatus as OnChainEventStatus,
   type PredictionEvent as OnChainEvent,
   calculateEstimatedPayout,
+  betSide,
 } from '@/lib/solana/programs/predictionMarket'
-import { getNetworkConfig } from '@/lib/solana/config'
 
 /** Generate unique ID */
 const generateId = (): ID => Math.random().toString(36).substring(2, 15)
@@ -41,10 +45,24 @@ const daysToMs = (days: number): number => days * 24 * 60 * 60 * 1000
 
 /** Convert on-chain event to frontend format */
 function onChainEventToFrontend(event: OnChainEvent, onChainId: number): PredictionEvent {
-  const statusMap: Record<OnChainEventStatus, 'active' | 'occurred' | 'expired'> = {
+  // Invalid and Draw return stakes rather than paying one side
+  const resolved: EventStatus =
+    event.outcome === Outcome.Doom
+      ? 'occurred'
+      : event.outcome === Outcome.Invalid || event.outcome === Outcome.Draw
+        ? 'refund'
+        : 'expired'
+  const statusMap: Record<OnChainEventStatus, EventStatus> = {
     [OnChainEventStatus.Active]: 'active',
-    [OnChainEventStatus.Resolved]: event.outcome === Outcome.Doom ? 'occurred' : 'expired',
-    [OnChainEventStatus.Cancelled]: 'expired',
+    [OnChainEventStatus.Resolved]: resolved,
+    [OnChainEventStatus.Cancelled]: 'refund',
+    [OnChainEventStatus.Expired]: 'refund',
+    [OnChainEventStatus.Closed]: 'active',
+    [OnChainEventStatus.Settled]: resolved,
+    // Awaiting a final outcome
+    [OnChainEventStatus.Proposed]: 'active',
+    [OnChainEventStatus.Disputed]: 'active',
+    [OnChainEventStatus.Swept]: resolved,
   }
 
   return {
@@ -362,10 +380,12 @@ export const useEventsStore = create<EventsState>()(
               const userBet = await fetchUserBet(connection, eventPDA, userPubkey)
 
               if (userBet) {
+                const side = betSide(userBet)
+
                 // Calculate estimated payout
                 const { payout } = calculateEstimatedPayout(
-                  userBet.amount.toNumber() / 1e9,
-                  userBet.outcome,
+                  side.amount.toNumber() / 1e9,
+                  side.outcome,
                   event.doomStake,
                   event.lifeStake,
                   200 // Default 2% fee
@@ -374,18 +394,25 @@ export const useEventsStore = create<EventsState>()(
                 const eventResolved = event.status !== 'active'
                 const isWinner =
                   eventResolved &&
-                  ((event.status === 'occurred' && userBet.outcome === Outcome.Doom) ||
-                    (event.status === 'expired' && userBet.outcome === Outcome.Life))
+                  ((event.status === 'occurred' && !userBet.doomAmount.isZero()) ||
+                    (event.status === 'expired' && !userBet.lifeAmount.isZero()))
+
+                // Refund states pay every bettor once, through claim_refund
+                // or, on a draw, claim_winnings
+                const canClaim =
+                  event.status === 'refund'
+                    ? !userBet.claimed && !userBet.refunded
+                    : isWinner && !userBet.claimed
 
                 onChainBets[event.onChainPDA] = {
                   eventId: event.id,
                   eventPDA: event.onChainPDA,
-                  outcome: userBet.outcome === Outcome.Doom ? 'doom' : 'life',
-                  amount: userBet.amount.toNumber() / 1e9,
+                  outcome: side.outcome === Outcome.Doom ? 'doom' : 'life',
+                  amount: side.amount.toNumber() / 1e9,
                   placedAt: userBet.placedAt.toNumber() * 1000,
                   claimed: userBet.claimed,
                   refunded: userBet.refunded,
-                  canClaim: isWinner && !userBet.claimed,
+                  canClaim,
                   estimatedPayout: payout,
                 }
               }
@@ -465,21 +492,38 @@ export const useEventsStore = create<EventsState>()(
       },
 
       claimWinningsOnChain: async (connection, user, eventId) => {
-        const config = getNetworkConfig()
-
-        // Fee accounts would typically be configured in the platform config
-        // For now, use placeholder addresses that would be set up during platform init
-        const doomFeeAccount = new PublicKey(config.tokens.doom.mint) // Placeholder
-        const lifeFeeAccount = new PublicKey(config.tokens.life.mint) // Placeholder
+        const event = await fetchEvent(connection, eventId)
+        if (!event?.tokenized) {
+          const refundable =
+            event?.status === OnChainEventStatus.Cancelled ||
+            event?.status === OnChainEventStatus.Expired ||
+            event?.outcome === Outcome.Invalid
+          const transaction = refundable
+            ? await buildClaimRefundTransaction(connection, user, eventId)
+            : await buildClaimWinningsTransaction(connection, user, eventId)
+          return { transaction }
+        }
 
-        const transaction = await buildClaimWinningsTransaction(
-          connection,
-          user,
-          eventId,
-          doomFeeAccount,
-          lifeFeeAccount
-        )
+        // Tokenized events pay out by redeeming shares: only the winning side
+        // on a decisive outcome, every side on a draw or refund
+        const sides =
+          event.outcome === Outcome.Doom || event.outcome === Outcome.Life
+            ? [event.outcome]
+            : [Outcome.Doom, Outcome.Life]
+        const shares: { outcome: typeof Outcome.Doom | typeof Outcome.Life; amount: BN }[] = []
+        for (const outcome of sides) {
+          const [shareMint] = findShareMintPDA(findEventPDA(eventId)[0], outcome)
+          const account = getAssociatedTokenAddressSync(shareMint, user)
+          const balance = await connection.getTokenAccountBalance(account).catch(() => null)
+          if (balance && balance.value.amount !== '0') {
+            shares.push({ outcome, amount: new BN(balance.value.amount) })
+          }
+        }
+        if (shares.length === 0) {
+          throw new Error('No shares to redeem')
+        }
 
+        const transaction = await buildRedeemSharesTransaction(connection, user, eventId, shares)
         return { transaction }
       },
 
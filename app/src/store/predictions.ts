@@ -15,6 +15,7 @@ import {
   fetchUserStats,
   fetchUserBets,
   Outcome,
+  betSide,
 } from '@/lib/solana/programs/predictionMarket'
 
 /**
@@ -335,15 +336,16 @@ export const usePredictionsStore = create<PredictionsState>()(
             )
 
             if (!existing) {
+              const { outcome, amount } = betSide(bet)
               newPredictions.push({
                 id: `onchain-${bet.event.toBase58().slice(0, 8)}-${user.toBase58().slice(0, 8)}`,
                 eventId,
                 userId,
-                side: bet.outcome === Outcome.Doom ? 'doom' : 'life',
-                amount: bet.amount.toNumber() / 1e9,
+                side: outcome === Outcome.Doom ? 'doom' : 'life',
+                amount: amount.toNumber() / 1e9,
                 createdAt: bet.placedAt.toNumber() * 1000,
-                resolved: bet.claimed || bet.refunded,
-                won: bet.claimed ? true : bet.refunded ? undefined : undefined,
+                resolved: bet.claimed || bet.refunded || bet.settledLoss,
+                won: bet.claimed ? true : bet.settledLoss ? false : undefined,
                 claimed: bet.claimed,
                 onChainEventPDA: eventPDA,
               })
@@ -108,8 +108,11 @@ export type EventCategory =
 
 /**
  * Event status in its lifecycle
+ *
+ * `refund` covers events voided, drawn or cancelled, where stakes are returned
+ * instead of paid to one side.
  */
-export type EventStatus = 'active' | 'occurred' | 'expired'
+export type EventStatus = 'active' | 'occurred' | 'expired' | 'refund'
 
 /**
  * Prediction event - a doom scenario with a countdown